@@ -0,0 +1,150 @@
+use std::fs;
+
+use crate::money::Money;
+
+use super::{built_in_text, plural::{Case, Noun}};
+
+// Reference goods the amounts can be expressed in besides Braník, read from a file with a good per line, i.e.
+//
+// rohlik|rohlik|rohlik|rohliky|rohliku|rohliku|3.90
+// kebab|kebab|kebab|kebaby|kebabu|kebabu|159|https://www.akcniceny.cz/...
+//
+// id|name for 1|name for 1 in accusative|name for 2 - 4|name for 5 and more|name for decimals|price
+// |optional url of akcniceny.cz page to scrape the price from
+//
+// the ids are the same in every language, only the names are translated
+pub struct GoodsCatalog {
+    goods: Vec<Good>
+}
+
+#[derive(Clone)]
+pub struct Good {
+    pub id: String,
+    name: Noun,
+    // static price, used as default one when the price is scraped
+    pub price: Money,
+    pub price_url: Option<String>
+}
+
+impl Good {
+    // "400 rohliku", None when the amount is not enough even for a single one
+    pub fn amount_text(&self, cash: Money, case: Case) -> Option<String> {
+        match cash.count(self.price) {
+            0 => None,
+            count => Some(self.name.text(count, case))
+        }
+    }
+}
+
+impl GoodsCatalog {
+    // the names are in the language of the reply templates
+    const BUILT_IN: [(&'static str, &'static str); 3] = [
+        ("cs", "rohlik|rohlik|rohlik|rohliky|rohliku|rohliku|3.90
+kebab|kebab|kebab|kebaby|kebabu|kebabu|159
+pilsner|Pilsner|Pilsner|Pilsnery|Pilsneru|Pilsneru|49.90
+fabia|Fabie|Fabii|Fabie|Fabii|Fabie|459900
+najem|mesicni najem v Praze|mesicni najem v Praze|mesicni najmy v Praze|mesicnich najmu v Praze|mesicniho najmu v Praze|28000"),
+        ("sk", "rohlik|rožok|rožok|rožky|rožkov|rožka|3.90
+kebab|kebab|kebab|kebaby|kebabov|kebabu|159
+pilsner|Pilsner|Pilsner|Pilsnery|Pilsnerov|Pilsnera|49.90
+fabia|Fabia|Fabiu|Fabie|Fabií|Fabie|459900
+najem|mesačný nájom v Prahe|mesačný nájom v Prahe|mesačné nájmy v Prahe|mesačných nájmov v Prahe|mesačného nájmu v Prahe|28000"),
+        ("en", "rohlik|bread roll|bread roll|bread rolls|bread rolls|bread rolls|3.90
+kebab|kebab|kebab|kebabs|kebabs|kebabs|159
+pilsner|Pilsner|Pilsner|Pilsners|Pilsners|Pilsners|49.90
+fabia|Fabia|Fabia|Fabias|Fabias|Fabias|459900
+najem|month of rent in Prague|month of rent in Prague|months of rent in Prague|months of rent in Prague|months of rent in Prague|28000")
+    ];
+
+    // the file has to be in the language of the reply templates, built-in goods are in the same language
+    pub fn load(path: &str, language: &str) -> GoodsCatalog {
+        match fs::read_to_string(path) {
+            Err(_) => {
+                println!("Failed to load goods from {}, using default ones", path);
+                Self::built_in(language)
+            },
+            Ok(text) => Self::read_goods(&text)
+        }
+    }
+
+    pub fn built_in(language: &str) -> GoodsCatalog {
+        let text = built_in_text(&Self::BUILT_IN, language, "goods");
+        Self::read_goods(text)
+    }
+
+    pub fn read_goods(goods_str: &str) -> GoodsCatalog {
+        let mut goods = vec![];
+        for line in goods_str.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let columns: Vec<&str> = line.split('|').map(|c| c.trim()).collect();
+            let (id, names, price, price_url) = match columns[..] {
+                [id, ref names @ .., price] if names.len() == 5 => (id, names, price, None),
+                [id, ref names @ .., price, url] => (id, names, price, Some(url.to_string())),
+                _ => {
+                    println!("Invalid goods line '{}'", line);
+                    continue;
+                }
+            };
+            match (Noun::from_columns(names), Money::parse(price)) {
+                (Some(name), Some(price)) if price.is_positive() => goods.push(Good { id: id.to_string(), name, price, price_url }),
+                _ => println!("Invalid goods line '{}'", line)
+            }
+        }
+        GoodsCatalog { goods }
+    }
+
+    // goods chosen for the subreddit in the given order, Braník is always the first one in the reply
+    // so it's never part of the selection
+    pub fn select(&self, ids: &[String]) -> Vec<Good> {
+        ids.iter()
+            .filter(|id| *id != "branik")
+            .filter_map(|id| {
+                let good = self.goods.iter().find(|g| &g.id == id);
+                if good.is_none() {
+                    println!("Unknown good '{}' in the config", id);
+                }
+                good.cloned()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_goods() {
+        let catalog = GoodsCatalog::read_goods("# comment\nrohlik|rohlik|rohlik|rohliky|rohliku|rohliku|3,90\nkebab|kebab|kebab|kebaby|kebabu|kebabu|159|https://kebab.cz\nspatne|radek\nzadarmo|a|b|c|d|e|0");
+        let goods = catalog.select(&["kebab".to_string(), "branik".to_string(), "rohlik".to_string(), "zadarmo".to_string()]);
+        assert_eq!(2, goods.len());
+        assert_eq!("kebab", goods[0].id);
+        assert_eq!(Some("https://kebab.cz".to_string()), goods[0].price_url);
+        assert_eq!("rohlik", goods[1].id);
+        assert_eq!(Money::from_halere(390), goods[1].price);
+        assert_eq!(None, goods[1].price_url);
+    }
+
+    #[test]
+    fn test_amount_text() {
+        let goods = GoodsCatalog::built_in("cs").select(&["rohlik".to_string(), "fabia".to_string(), "kebab".to_string()]);
+        assert_eq!(Some("1 rohlik".to_string()), goods[0].amount_text(Money::from_halere(390), Case::Accusative));
+        assert_eq!(Some("128 rohliku".to_string()), goods[0].amount_text(Money::from_units(500), Case::Accusative));
+        assert_eq!(None, goods[1].amount_text(Money::from_units(500), Case::Accusative));
+        assert_eq!(Some("2 Fabie".to_string()), goods[1].amount_text(Money::from_units(1000000), Case::Accusative));
+        assert_eq!(Some("3 kebaby".to_string()), goods[2].amount_text(Money::from_units(500), Case::Accusative));
+        assert_eq!(Some("1 Fabie".to_string()), goods[1].amount_text(Money::from_units(500000), Case::Nominative));
+    }
+
+    #[test]
+    fn test_built_in() {
+        let ids = ["rohlik", "kebab", "pilsner", "fabia", "najem"].map(|id| id.to_string());
+        for (language, _) in GoodsCatalog::BUILT_IN {
+            assert_eq!(ids.len(), GoodsCatalog::built_in(language).select(&ids).len(), "invalid built-in goods for {}", language);
+        }
+        let goods = GoodsCatalog::built_in("en").select(&ids);
+        assert_eq!(Some("128 bread rolls".to_string()), goods[0].amount_text(Money::from_units(500), Case::Accusative));
+        assert_eq!(Some("1 Fabia".to_string()), goods[3].amount_text(Money::from_units(500000), Case::Accusative));
+        let goods = GoodsCatalog::built_in("sk").select(&ids);
+        assert_eq!(Some("3 rožky".to_string()), goods[0].amount_text(Money::from_units(12), Case::Accusative));
+    }
+}
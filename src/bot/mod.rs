@@ -1,267 +1,477 @@
-use core::time;
-use std::{thread, fs, io::Write, ops::Sub};
+use std::{fmt, fs, io::Write, ops::Sub, process, sync::{Arc, RwLock}, time::Duration};
 
-use chrono::Local;
-use roux::{Reddit, Me, comment::CommentData, Subreddit, User};
-use crate::{parser::{Parser, ParseResult}, comment_reader::CommentReader};
-use self::price_reader::PriceReader;
+use chrono::{Local, NaiveDate};
+use tokio::task::JoinHandle;
+use crate::{parser::{Parser, ParseResult, currency::Currency, period::Period, quantity::Packaging}, comment_reader::CommentReader, money::Money, rates::ExchangeRates, reddit::{Comment, RedditApi, http::HttpReddit}};
+use self::{goods::{Good, GoodsCatalog}, packaging::{BranikAmount, PackagingModel}, plural::Case, prices::{PriceSources, akcniceny::AkcniCeny, history::PriceHistory, refresh::{BranikPrice, RefreshSchedule}, sanity::PriceGuard}, templates::Templates};
 
 use super::config::Config;
 
-pub mod price_reader;
+pub mod goods;
+pub mod packaging;
+pub mod plural;
+pub mod prices;
+pub mod templates;
 
-pub struct BranikBot {
+pub struct BranikBot<R: RedditApi> {
     config: Config,
-    reddit_client: Option<Me>,
+    reddit: R,
     comment_reader: CommentReader,
-    price_reader: PriceReader,
     parser: Parser,
-    user: User,
-    branik_price: f32
+    exchange_rates: ExchangeRates,
+    packaging: PackagingModel,
+    // shared with the task refreshing the prices
+    goods: Arc<RwLock<Vec<Good>>>,
+    templates: Templates,
+    // shared with the task refreshing it
+    branik_price: Arc<BranikPrice>
 }
 
-enum BranikAmount {
-    Pet(u32),
-    Pack(u32),
-    Palett(u32, u32)
+// why a comment doesn't get a response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    OwnComment,
+    // i.e. u/fabia-bot or AutoModerator
+    IgnoredAuthor,
+    // comments of the bot already on the post
+    PostLimit(usize),
+    NothingFound,
+    // only low confidence values or currencies without exchange rate
+    NothingToReply
 }
 
-impl BranikBot {
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::OwnComment => write!(f, "own comment"),
+            SkipReason::IgnoredAuthor => write!(f, "ignored author"),
+            SkipReason::PostLimit(count) => write!(f, "already {} comments on the post", count),
+            SkipReason::NothingFound => write!(f, "no value found"),
+            SkipReason::NothingToReply => write!(f, "no value to reply to")
+        }
+    }
+}
+
+// dry run never logs in nor posts the responses
+pub fn load_config(config_path: &str, dry_run: bool) -> Config {
+    let mut config = match Config::load(config_path) {
+        Ok(config) => config,
+        Err(errors) => {
+            println!("Invalid config {}:", config_path);
+            for error in errors {
+                println!("  {}", error);
+            }
+            process::exit(1);
+        }
+    };
+    if dry_run {
+        config.post_response = false;
+    }
+    config
+}
+
+// the built-in table of goods, packaging or templates in the language, czech one for the others
+fn built_in_text(table: &[(&'static str, &'static str)], language: &str, name: &str) -> &'static str {
+    const FALLBACK_LANGUAGE: &str = "cs";
+    table.iter()
+        .find(|(l, _)| *l == language)
+        .or_else(|| {
+            println!("No built-in {} for language '{}', using '{}'", name, language, FALLBACK_LANGUAGE);
+            table.iter().find(|(l, _)| *l == FALLBACK_LANGUAGE)
+        })
+        .map(|(_, text)| *text)
+        .expect("Expected built-in czech table")
+}
+
+// goods with a page to scrape, the price of a failed one is kept
+async fn update_goods_prices(goods: &RwLock<Vec<Good>>) {
+    let pages: Vec<(String, String)> = goods.read().unwrap().iter()
+        .filter_map(|g| g.price_url.clone().map(|url| (g.id.clone(), url)))
+        .collect();
+    for (id, url) in pages {
+        let quote = PriceSources::new(vec![Box::new(AkcniCeny::new(url))]).lowest_price().await;
+        let mut goods = goods.write().unwrap();
+        let Some(good) = goods.iter_mut().find(|g| g.id == id) else {
+            continue;
+        };
+        match quote {
+            Some(quote) => good.price = quote.price,
+            None => println!("Failed to update price of {}, keeping {} Kč", good.id, good.price)
+        }
+    }
+}
 
-    const RESPONSE_PREFIX: &'static str = "To by stacilo na ";
-    const RESPONSE_SUFFIX: &'static str = "Branika ve sleve!";
-    const RUN_DURATION_MINUTES_LIMIT: i64 = 60 * 4;
+impl BranikBot<HttpReddit> {
+    pub async fn respawn(config_path: &str, dry_run: bool) -> Self {
+        let config = load_config(config_path, dry_run);
+        let reddit = HttpReddit::connect(&config).await;
+        BranikBot::new(config, reddit)
+    }
+}
 
-    pub async fn respawn() -> Self {
-        let config = Config::load();
-        let reddit_client = Self::login(&config).await;
+impl<R: RedditApi> BranikBot<R> {
+
+    const PET_LITERS: f64 = 2.0;
+
+    pub fn new(config: Config, reddit: R) -> Self {
         let parser = Parser::new();
-        let price_reader = PriceReader {};
         let comment_reader = CommentReader { 
-            subreddit: Subreddit::new(&config.subreddit),
-            last_comment_storage_path: "./data/last_comment".to_string()
+            subreddit: config.subreddit.clone(),
+            last_comment_storage_path: config.data_path("last_comment")
         };
-        let user = User::new(&config.user_name);
-        let default_price = config.default_price;
+        let exchange_rates = ExchangeRates::load(&config.exchange_rates_path, config.exchange_rates_max_age_days);
+        let templates = Templates::load(&config.templates_dir, &config.subreddit, &config.language);
+        let mut packaging = PackagingModel::load(&config.packaging_path, &config.language);
+        packaging.set_words(templates.get("and"), templates.get("to"));
+        let goods = GoodsCatalog::load(&config.goods_path, &config.language).select(&config.goods);
+        // the sources are validated with the config
+        let price_sources = PriceSources::from_specs(&config.price_sources).unwrap_or(PriceSources::new(vec![]));
+        let price_guard = PriceGuard::new(config.min_price, config.max_price, config.max_price_change_percent,
+            PriceHistory::load(&config.price_quarantine_path));
+        let branik_price = Arc::new(BranikPrice::new(price_sources, PriceHistory::load(&config.price_history_path), price_guard,
+            config.default_price, config.price_history_max_age_days));
         BranikBot { 
             config,
-            reddit_client,
+            reddit,
             comment_reader,
             parser,
-            user,
-            price_reader,
-            branik_price: default_price
-        }
-    }
-
-    async fn login(config: &Config) -> Option<Me> { 
-        if !config.post_response {
-            println!("POST_RESPONSE set to false, skipping Login");
-            return None;
+            exchange_rates,
+            packaging,
+            goods: Arc::new(RwLock::new(goods)),
+            templates,
+            branik_price
         }
-        println!("Loggin in");
-        let client = Reddit::new(&config.user_agent, &config.client_id, &config.client_secret)
-            .username(&config.user_name)
-            .password(&config.password)
-            .login().await;
-        match client {
-            Err(_) => {
-                if config.post_response {
-                    panic!("Couldn't login to reddit and POST_RESPONSE is set to true");
-                }
-                None
-            },
-            Ok(me) => Some(me)
-        } 
     }
 
     pub async fn run(&mut self) {
         let start = Local::now();
         self.update_price().await;
+        let refresh = self.spawn_refresh(self.refresh_schedule());
         loop {
-            if Local::now().sub(start).num_minutes() > Self::RUN_DURATION_MINUTES_LIMIT {
+            if Local::now().sub(start).num_minutes() > self.config.run_duration_minutes {
                 // restart the bot after few hours
                 break;
             }
-            println!("\nRead new comments!");
-            match self.comment_reader.read_latest_comments().await {
-                None => {
-                    self.sleep();
-                    continue;
-                },
-                Some(comments) => {
-                    println!("Found {} new comments!", comments.len());
-                    self.parse_comments_and_create_responses(comments).await;
-                }
-            }
-            self.sleep(); 
+            self.read_and_respond().await;
+            self.sleep().await;
+        }
+        if let Some(refresh) = refresh {
+            refresh.abort();
         }
     }
 
-    fn sleep(&self) {
-        thread::sleep(time::Duration::from_secs(60 * 5));
+    async fn read_and_respond(&mut self) {
+        println!("\nRead new comments!");
+        if let Some(comments) = self.comment_reader.read_latest_comments(&self.reddit).await {
+            println!("Found {} new comments!", comments.len());
+            self.parse_comments_and_create_responses(comments).await;
+        }
     }
 
-    async fn update_price(&mut self) {
-        println!("Update price!");
-        let price = if let Ok(p) = self.price_reader.load_and_parse_branik_price(self.config.default_price).await {
-            p
-        } else {
-            self.config.default_price
-        };
-        if price > 0.0 {
-            self.branik_price = price;
-        } else {
-            self.branik_price = self.config.default_price
+    // the refresh task runs meanwhile
+    async fn sleep(&self) {
+        tokio::time::sleep(Duration::from_secs(self.config.poll_interval_seconds)).await;
+    }
+
+    fn refresh_schedule(&self) -> RefreshSchedule {
+        RefreshSchedule {
+            interval: Some(self.config.price_refresh_hours).filter(|h| *h > 0).map(|h| Duration::from_secs(h * 60 * 60)),
+            daily_times: self.config.price_refresh_times.clone()
+        }
+    }
+
+    // the goods are refreshed in the same tick as Braník
+    fn spawn_refresh(&self, schedule: RefreshSchedule) -> Option<JoinHandle<()>> {
+        let branik_price = self.branik_price.clone();
+        let goods = self.goods.clone();
+        schedule.spawn(move || {
+            let (branik_price, goods) = (branik_price.clone(), goods.clone());
+            async move {
+                branik_price.update().await;
+                update_goods_prices(&goods).await;
+            }
+        })
+    }
+
+    pub async fn update_price(&self) {
+        self.branik_price.update().await;
+        update_goods_prices(&self.goods).await;
+    }
+
+    pub fn print_prices(&self) {
+        let current = self.branik_price.current();
+        match current.record {
+            Some(record) => println!("Branik: {}, found {}", record.quote, record.time.format("%d.%m.%Y %H:%M")),
+            None => println!("Branik: {} Kč (default)", current.price)
+        }
+        for good in self.goods.read().unwrap().iter() {
+            println!("{}: {} Kč", good.id, good.price);
+        }
+    }
+
+    // recorded Braník prices of the days, including both
+    pub fn print_price_history(&self, from: NaiveDate, to: NaiveDate) {
+        let history = self.branik_price.history();
+        let records = history.between(from, to);
+        if records.is_empty() {
+            println!("No prices recorded from {} to {}", from, to);
+        }
+        for record in records {
+            println!("{}: {}", record.time.format("%d.%m.%Y %H:%M"), record.quote);
         }
     }
 
     async fn load_post_ids_for_posted_comments(&self) -> Vec<String> {
-        let mut post_ids_for_bot_comments: Vec<String> = vec![];
-        match &self.user.comments(
-            Some(roux::util::FeedOption {
-                after: None,
-                before: None,
-                limit: None,
-                count: None,
-                period: Some(roux::util::TimePeriod::Today) 
-            })).await {
-                Ok(comments_from_bot) => {
-                    for comment in comments_from_bot.data.children.iter() {
-                        post_ids_for_bot_comments.push(comment.data.link_id.clone().unwrap());
-                    }
-                },
-                Err(_) => println!("Wasn't able to load comments from bot")
+        match self.reddit.user_comments(&self.config.user_name).await {
+            Ok(comments_from_bot) => comments_from_bot.into_iter().map(|c| c.link_id).collect(),
+            Err(e) => {
+                println!("Wasn't able to load comments from bot: {}", e);
+                vec![]
             }
-        post_ids_for_bot_comments
+        }
     }
 
-    async fn parse_comments_and_create_responses(&self, comments: Vec<CommentData>) {
+    async fn parse_comments_and_create_responses(&self, comments: Vec<Comment>) {
         let post_ids_for_posted_comments = self.load_post_ids_for_posted_comments().await;
         for comment in comments.iter() {
-            // lets not react to my own comments here
-            if comment.author.clone().unwrap() == self.config.user_name {
-                continue;
-            }
-            let comments_on_post_count = post_ids_for_posted_comments.iter()
-                // count current comment "LINK_ID (= post id)" occurencies in bot comments
-                .filter(|pid| pid.to_owned() == &comment.link_id.clone().unwrap_or("".to_string())).count();
-            if comments_on_post_count >= self.config.comments_per_post_limit {
-                println!("Already posted {} comments on this post {}, limit is {}, skipping...",
-                    comments_on_post_count, &comment.link_url.clone().unwrap(), self.config.comments_per_post_limit);
-                continue;
+            match self.review_comment(comment, &post_ids_for_posted_comments) {
+                Err(SkipReason::PostLimit(count)) => println!("Already posted {} comments on this post {}, limit is {}, skipping...",
+                    count, comment.link_url, self.config.comments_per_post_limit),
+                Err(_) => continue,
+                Ok(response) => self.post_response(&response, &comment.name).await
             }
-            let comment_body = if let Some(cb) = &comment.body {
-                cb
-            } else {
-                // No comment body - nothing to parse
-                continue;
-            };
-            let results = if let Some(r) = self.parser.parse(&comment_body) {
-                r
-            } else {
-                // No matches in comment 
-                continue;
-            };
-            self.post_response(
-                &self.generate_message_for_results(&results),
-                &comment.name.clone().unwrap().to_string()).await;
-        }
-    }
-
-    fn generate_message_for_results(&self, parse_results: &Vec<ParseResult>) -> String {
+        }
+    }
+
+    // responses to comments of a dump in the order they were written, nothing is posted and the bot
+    // has no earlier comments
+    pub fn replay(&self, comments: &[Comment]) -> Vec<Result<String, SkipReason>> {
+        let mut post_ids_for_posted_comments = vec![];
+        comments.iter()
+            .map(|comment| {
+                let response = self.review_comment(comment, &post_ids_for_posted_comments);
+                if response.is_ok() {
+                    post_ids_for_posted_comments.push(comment.link_id.clone());
+                }
+                response
+            })
+            .collect()
+    }
+
+    fn review_comment(&self, comment: &Comment, post_ids_for_posted_comments: &[String]) -> Result<String, SkipReason> {
+        // lets not react to my own comments here
+        if comment.author == self.config.user_name {
+            return Err(SkipReason::OwnComment);
+        }
+        // nor to other bots, i.e. u/fabia-bot or AutoModerator
+        if self.is_ignored_author(&comment.author) {
+            return Err(SkipReason::IgnoredAuthor);
+        }
+        let comments_on_post_count = post_ids_for_posted_comments.iter()
+            // count current comment "LINK_ID (= post id)" occurencies in bot comments
+            .filter(|pid| **pid == comment.link_id).count();
+        if comments_on_post_count >= self.config.comments_per_post_limit {
+            return Err(SkipReason::PostLimit(comments_on_post_count));
+        }
+        self.response_for(&comment.body)
+    }
+
+    // all results found in the comment, including the ones not replied to
+    pub fn parse_comment(&self, comment_body: &str) -> Vec<ParseResult> {
+        self.parser.parse_comment(comment_body).unwrap_or_default()
+    }
+
+    pub fn generate_response(&self, comment_body: &str) -> Option<String> {
+        self.response_for(comment_body).ok()
+    }
+
+    fn response_for(&self, comment_body: &str) -> Result<String, SkipReason> {
+        let mut results = self.parse_comment(comment_body);
+        if results.is_empty() {
+            return Err(SkipReason::NothingFound);
+        }
+        // drop foreign amounts we don't have exchange rate for and values which might not be money
+        results.retain(|r| self.can_generate_result_row(r));
+        if results.is_empty() {
+            return Err(SkipReason::NothingToReply);
+        }
+        Ok(self.generate_message_for_results(&results))
+    }
+
+    // reddit user names are case insensitive
+    fn is_ignored_author(&self, author: &str) -> bool {
+        self.config.ignored_authors.iter().any(|a| a.eq_ignore_ascii_case(author))
+    }
+
+    fn generate_message_for_results(&self, parse_results: &[ParseResult]) -> String {
         let mut result_message = "".to_string();
         for result in parse_results {
             result_message += &self.generate_result_row(result);
         }
-        result_message += &format!("\n\n^(Jsem bot, doufam, ze poskytnuta informace byla uzitecna. Podnety - Stiznosti - QA na r/branicek)").to_string();
+        result_message += &format!("\n\n{}", self.templates.get("footer"));
         result_message
     }
 
+    pub fn can_generate_result_row(&self, parse_result: &ParseResult) -> bool {
+        if parse_result.text_match().confidence < self.config.min_confidence {
+            return false;
+        }
+        match parse_result {
+            ParseResult::ForeignValue(_, value, currency) => self.exchange_rates.to_czk(*value, *currency).is_some(),
+            ParseResult::Periodic(result, _) => self.can_generate_result_row(result),
+            ParseResult::Quantity(_, count, packaging) => self.get_quantity_price(*count, *packaging).is_some(),
+            _ => true
+        }
+    }
+
     fn generate_result_row(&self, parse_result: &ParseResult) -> String {
         match parse_result {
-            ParseResult::Keyword => self.generate_keyword_result_row(),
-            ParseResult::Value(parsed_value, result_value) => self.generate_value_result_row(parsed_value, *result_value)
+            ParseResult::Keyword(_) => self.generate_keyword_result_row(),
+            // quote whole sentence, not just the bare value
+            ParseResult::Value(parsed_value, result_value) => self.generate_value_result_row(parsed_value.quote(), *result_value),
+            ParseResult::ForeignValue(parsed_value, result_value, currency) =>
+                self.generate_foreign_value_result_row(parsed_value.quote(), *result_value, *currency),
+            ParseResult::Range(parsed_value, from, to) => self.generate_range_result_row(parsed_value.quote(), *from, *to),
+            ParseResult::Quantity(parsed_value, count, packaging) =>
+                self.generate_quantity_result_row(parsed_value.quote(), *count, *packaging),
+            ParseResult::Periodic(result, period) => self.generate_periodic_result_row(result, *period)
         }
     }
 
     fn generate_keyword_result_row(&self) -> String {
-        format!("Dvoulitrovka Branika ve sleve aktualne stoji {}0 Kč", self.branik_price.to_string().replace(".", ","))
-    }
-
-    fn generate_value_result_row(&self, parsed_value: &String, parsed_result: f32) -> String {
-        let row = format!("> {}\n\n", parsed_value);
-        match self.get_branik_amount(parsed_result) {
-            BranikAmount::Pet(amount) => {
-                if amount == 0 {
-                    format!("{}Je mi to lito, ale to neni ani na jednu dvoulitrovku Branika ve sleve.\n\n", row)
-                } else {
-                    format!("{}{}{} dvoulitrov{} {}\n\n",
-                        row,
-                        Self::RESPONSE_PREFIX,
-                        amount,
-                        match amount {
-                            1 => "ku",
-                            2..=4 => "ky",
-                            _ => "ek" 
-                        },
-                        Self::RESPONSE_SUFFIX)
-                }
+        let date = Local::now().format("%d.%m.%Y").to_string();
+        self.templates.render("keyword", &[("price", &self.branik_price.price().to_string()), ("date", &date)])
+    }
+
+    fn generate_foreign_value_result_row(&self, parsed_value: &str, parsed_result: Money, currency: Currency) -> String {
+        let czk_value = self.exchange_rates.to_czk(parsed_result, currency).unwrap_or(Money::ZERO);
+        let row = self.templates.render("foreign",
+            &[("amount", &parsed_result.to_string()), ("unit", currency.code()), ("price", &czk_value.to_string())]);
+        format!("{}\n\n{} {}\n\n", self.generate_quote_row(parsed_value), row, self.generate_value_text(czk_value))
+    }
+
+    fn generate_quantity_result_row(&self, parsed_value: &str, count: f64, packaging: Packaging) -> String {
+        let text = match (self.get_bottle_count(count, packaging), self.get_quantity_price(count, packaging)) {
+            (Some(bottles), Some(price)) => self.templates.render("quantity",
+                &[("amount", &self.packaging.bottles_text(bottles, Case::Accusative)), ("price", &price.to_string())]),
+            _ => self.templates.get("quantity_too_big").to_string()
+        };
+        format!("{}\n\n{}\n\n", self.generate_quote_row(parsed_value), text)
+    }
+
+    // only whole bottles can be bought - "3 litry" are 2 dvoulitrovky
+    fn get_bottle_count(&self, count: f64, packaging: Packaging) -> Option<u64> {
+        let bottles = match packaging {
+            Packaging::Pet => count,
+            Packaging::Pack => count * self.packaging.size_of(PackagingModel::PACK)? as f64,
+            Packaging::Palett => count * self.packaging.size_of(PackagingModel::PALETT)? as f64,
+            Packaging::Liter => count / Self::PET_LITERS
+        };
+        Some(bottles.ceil() as u64)
+    }
+
+    fn get_quantity_price(&self, count: f64, packaging: Packaging) -> Option<Money> {
+        let bottles = i64::try_from(self.get_bottle_count(count, packaging)?).ok()?;
+        self.branik_price.price().checked_mul(bottles)
+    }
+
+    fn generate_value_result_row(&self, parsed_value: &str, parsed_result: Money) -> String {
+        format!("{}\n\n{}\n\n", self.generate_quote_row(parsed_value), self.generate_value_text(parsed_result))
+    }
+
+    // Braník and the other goods the value is enough for
+    fn generate_value_text(&self, parsed_result: Money) -> String {
+        let amount = self.get_branik_amount(parsed_result);
+        let (text, goods_key) = match amount.is_empty() {
+            true => (self.templates.get("nothing").to_string(), "nothing_goods"),
+            false => (self.templates.render("amount", &[("amount", &self.packaging.text(&amount, Case::Accusative))]), "goods")
+        };
+        match self.generate_goods_text(parsed_result) {
+            None => text,
+            Some(goods_text) => format!("{} {}", text, self.templates.render(goods_key, &[("amount", &goods_text)]))
+        }
+    }
+
+    fn generate_quote_row(&self, parsed_value: &str) -> String {
+        self.templates.render("quote", &[("quote", parsed_value)])
+    }
+
+    // other goods chosen for the subreddit - "400 rohliku, 3 kebaby"
+    fn generate_goods_text(&self, cash: Money) -> Option<String> {
+        let texts: Vec<String> = self.goods.read().unwrap().iter().filter_map(|g| g.amount_text(cash, Case::Accusative)).collect();
+        match texts.is_empty() {
+            true => None,
+            false => Some(texts.join(", "))
+        }
+    }
+
+    fn generate_range_result_row(&self, parsed_value: &str, from: Money, to: Money) -> String {
+        let text = match self.generate_range_amount_text(from, to, Case::Accusative) {
+            None => self.templates.get("nothing").to_string(),
+            Some(amount_text) => self.templates.render("amount", &[("amount", &amount_text)])
+        };
+        format!("{}\n\n{}\n\n", self.generate_quote_row(parsed_value), text)
+    }
+
+    fn generate_periodic_result_row(&self, parse_result: &ParseResult, period: Period) -> String {
+        let row = self.generate_result_row(parse_result);
+        let daily_amount_text = match parse_result {
+            ParseResult::Value(_, value) => self.generate_daily_amount_text(period.to_daily(*value)),
+            ParseResult::ForeignValue(_, value, currency) => match self.exchange_rates.to_czk(*value, *currency) {
+                Some(czk_value) => self.generate_daily_amount_text(period.to_daily(czk_value)),
+                None => return row
             },
-            BranikAmount::Pack(amount) => {
-                format!("{}{}{} baliku dvoulitrovek {}\n\n",
-                    row,
-                    Self::RESPONSE_PREFIX,
-                    amount,
-                    Self::RESPONSE_SUFFIX)
+            ParseResult::Range(_, from, to) => {
+                let (from, to) = (period.to_daily(*from), period.to_daily(*to));
+                self.generate_range_amount_text(from, to, Case::Nominative).unwrap_or_else(|| self.generate_daily_amount_text(to))
             },
-            BranikAmount::Palett(amount, pack_amount) => {
-                format!("{}{}vic jak {} palet{} ({} baliku) dvoulitrovek {}\n\n",
-                    row,
-                    Self::RESPONSE_PREFIX,
-                    amount,
-                    match amount {
-                        1 => "u",
-                        2..=4 => "y",
-                        _ => ""
-                    },
-                    pack_amount,
-                    Self::RESPONSE_SUFFIX)
-            }
+            _ => return row
+        };
+        format!("{} {}\n\n", row.trim_end(), self.templates.render("daily", &[("amount", &daily_amount_text)]))
+    }
+
+    fn generate_daily_amount_text(&self, daily_value: Money) -> String {
+        let amount = self.get_branik_amount(daily_value);
+        if !amount.is_empty() {
+            return self.packaging.text(&amount, Case::Nominative);
+        }
+        // part of a dvoulitrovka - "0,25 dvoulitrovky"
+        let bottles = daily_value.halere() as f64 / self.branik_price.price().halere() as f64;
+        match bottles >= 0.01 {
+            true => self.packaging.decimal_bottles_text(bottles, Case::Nominative),
+            false => self.templates.get("daily_less").to_string()
         }
     }
 
-    fn get_branik_amount(&self, cash: f32) -> BranikAmount {
-        let amount = (cash / self.branik_price) as u32;
-        match amount {
-            0 => BranikAmount::Pet(0), 
-            // 144 = half of a palett
-            1..=144 => BranikAmount::Pet(amount),
-            // 288 = full palett
-            145..=288 => BranikAmount::Pack(amount / 6),
-            289.. => BranikAmount::Palett(amount / 288, amount / 6)
+    fn generate_range_amount_text(&self, from: Money, to: Money, case: Case) -> Option<String> {
+        let (from, to) = (self.get_branik_amount(from), self.get_branik_amount(to));
+        if to.is_empty() {
+            return None;
         }
+        Some(self.packaging.range_text(&from, &to, case))
+    }
+
+    fn get_branik_amount(&self, cash: Money) -> BranikAmount {
+        self.packaging.amount(cash.count(self.branik_price.price()))
     }
 
     async fn post_response(&self, response: &str, comment_id: &str) {
         println!("\nCreated response \n{}\nto comment {}", response, comment_id);
         if self.config.post_response {
             println!("Posting response...");
-            let r = self.reddit_client.as_ref().expect("Expected reddit client being logged in").comment(response, comment_id).await;
-            match r {
+            match self.reddit.post_reply(comment_id, response).await {
                 Ok(_) => (),
-                Err(err_response) => println!("Error posting response {}", err_response.to_string())
+                Err(err_response) => println!("Error posting response {}", err_response)
             }
         }
         if self.config.save_response {
             println!("Saving response...");
             let open_file = fs::OpenOptions::new()
-                .write(true)
                 .create(true)
                 .append(true)
-                .open("./data/comments_from_bot");
+                .open(self.config.data_path("comments_from_bot"));
             match open_file {
-                Err(e) => println!("Cant open file! {}", e.to_string()),
+                Err(e) => println!("Cant open file! {}", e),
                 Ok(mut file) => {
                     let _ = file.write_all(response.as_bytes()); 
                 },
@@ -272,41 +482,359 @@ impl BranikBot {
 
 #[cfg(test)]
 mod tests {
+    use crate::{bot::prices::PriceQuote, reddit::{fake_server::FakeReddit, memory::MemoryReddit}};
+
     use super::*;
 
+    const RESPONSE_PREFIX: &str = "To by stacilo na ";
+    const RESPONSE_SUFFIX: &str = "Branika ve sleve!";
+
     #[tokio::test]
     async fn test_result_row() {
-        let test_bot = BranikBot::respawn().await;
-        let parse_result = ParseResult::Value( "20 kc".to_string(), 20.0);
+        let test_bot = memory_bot("result-row");
+        let rows = [
+            ("20 kc", 20, None),
+            ("50kc", 50, Some("1 dvoulitrovku")),
+            ("150kc", 150, Some("3 dvoulitrovky")),
+            ("650kc", 650, Some("2 baliky a 4 dvoulitrovky")),
+            ("10k", 10000, Some("2 vrstvy a 9 baliku")),
+            ("20k", 20000, Some("1 paletu a 2 vrstvy")),
+            ("30k", 30000, Some("2 palety a 1 vrstvu")),
+            ("150k", 150000, Some("13 palet a 2 baliky")),
+            ("2 mega", 2000000, Some("3 vagony a 18 palet")),
+            ("3 mld", 3000000000, Some("5020 vagonu a 29 palet"))
+        ];
+        for (text, value, amount_text) in rows {
+            let parse_result = ParseResult::Value(text.into(), Money::from_units(value));
+            let response_row = test_bot.generate_result_row(&parse_result);
+            match amount_text {
+                None => assert_eq!(response_row, format!("> {}\n\nJe mi to lito, ale to neni ani na jednu dvoulitrovku Branika ve sleve.\n\n", text)),
+                Some(amount_text) => assert_eq!(response_row,
+                    format!("> {}\n\n{}{} {}\n\n", text, RESPONSE_PREFIX, amount_text, RESPONSE_SUFFIX))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_range_result_row() {
+        let test_bot = memory_bot("range-result-row");
+        let parse_result = ParseResult::Range("30 - 50 kč".into(), Money::from_units(30), Money::from_units(50));
+        let response_row = test_bot.generate_result_row(&parse_result);
+        assert_eq!(response_row, format!("> 30 - 50 kč\n\n{}0 až 1 dvoulitrovku {}\n\n", RESPONSE_PREFIX, RESPONSE_SUFFIX));
+        let parse_result = ParseResult::Range("100-200 kc".into(), Money::from_units(100), Money::from_units(200));
         let response_row = test_bot.generate_result_row(&parse_result);
-        assert_eq!(response_row, format!("> 20 kc\n\nJe mi to lito, ale to neni ani na jednu dvoulitrovku Branika ve sleve.\n\n"));
-        let parse_result = ParseResult::Value( "50kc".to_string(), 50.0);
+        assert_eq!(response_row, format!("> 100-200 kc\n\n{}2 až 5 dvoulitrovek {}\n\n", RESPONSE_PREFIX, RESPONSE_SUFFIX));
+        let parse_result = ParseResult::Range("3-10k".into(), Money::from_units(3000), Money::from_units(10000));
         let response_row = test_bot.generate_result_row(&parse_result);
-        assert_eq!(response_row, format!("> 50kc\n\n{}{} dvoulitrovku {}\n\n", BranikBot::RESPONSE_PREFIX, (50.0 / test_bot.config.default_price) as i32, BranikBot::RESPONSE_SUFFIX));
-        let parse_result = ParseResult::Value( "150kc".to_string(), 150.0);
+        assert_eq!(response_row, format!("> 3-10k\n\n{}12 baliku a 3 dvoulitrovky až 2 vrstvy a 9 baliku {}\n\n", RESPONSE_PREFIX, RESPONSE_SUFFIX));
+        let parse_result = ParseResult::Range("5-10 kc".into(), Money::from_units(5), Money::from_units(10));
         let response_row = test_bot.generate_result_row(&parse_result);
-        assert_eq!(response_row, format!("> 150kc\n\n{}{} dvoulitrovky {}\n\n", BranikBot::RESPONSE_PREFIX, (150.0 / test_bot.config.default_price) as i32, BranikBot::RESPONSE_SUFFIX));
-        let parse_result = ParseResult::Value( "650kc".to_string(), 650.0);
+        assert_eq!(response_row, format!("> 5-10 kc\n\nJe mi to lito, ale to neni ani na jednu dvoulitrovku Branika ve sleve.\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_periodic_result_row() {
+        let test_bot = memory_bot("periodic-result-row");
+        let parse_result = ParseResult::Periodic(Box::new(ParseResult::Value("45k měsíčně".into(), Money::from_units(45000))), Period::Month);
         let response_row = test_bot.generate_result_row(&parse_result);
-        assert_eq!(response_row, format!("> 650kc\n\n{}{} dvoulitrovek {}\n\n", BranikBot::RESPONSE_PREFIX, (650.0 / test_bot.config.default_price) as i32, BranikBot::RESPONSE_SUFFIX));
-        let parse_result = ParseResult::Value("10k".to_string(), 10000.0);
+        assert_eq!(response_row, format!("> 45k měsíčně\n\n{}3 palety a 2 vrstvy {} To je 6 baliku a 1 dvoulitrovka denně.\n\n",
+            RESPONSE_PREFIX, RESPONSE_SUFFIX));
+        let parse_result = ParseResult::Periodic(Box::new(ParseResult::Value("40 kč/hod".into(), Money::from_units(40))), Period::Hour);
         let response_row = test_bot.generate_result_row(&parse_result);
-        assert_eq!(response_row, format!("> 10k\n\n{}{} baliku dvoulitrovek {}\n\n", BranikBot::RESPONSE_PREFIX, (10000.0 / test_bot.config.default_price / 6.0) as i32, BranikBot::RESPONSE_SUFFIX));
-        let parse_result = ParseResult::Value( "20k".to_string(), 20000.0);
+        assert_eq!(response_row, format!("> 40 kč/hod\n\n{}1 dvoulitrovku {} To je 1 balik a 2 dvoulitrovky denně.\n\n",
+            RESPONSE_PREFIX, RESPONSE_SUFFIX));
+        let parse_result = ParseResult::Periodic(Box::new(ParseResult::Range("20-25k za měsíc".into(), Money::from_units(20000), Money::from_units(25000))), Period::Month);
         let response_row = test_bot.generate_result_row(&parse_result);
-        assert_eq!(response_row, format!("> 20k\n\n{}vic jak {} paletu ({} baliku) dvoulitrovek {}\n\n", BranikBot::RESPONSE_PREFIX, (20000.0 / (12.0*8.0*3.0*test_bot.config.default_price)) as i32, (20000.0 / test_bot.config.default_price / 6.0) as i32, BranikBot::RESPONSE_SUFFIX));
-        let parse_result = ParseResult::Value("30k".to_string(), 30000.0);
+        assert_eq!(response_row, format!("> 20-25k za měsíc\n\n{}1 paletu a 2 vrstvy až 2 palety a 8 baliku {} To je 2 baliky a 4 dvoulitrovky až 3 baliky a 2 dvoulitrovky denně.\n\n",
+            RESPONSE_PREFIX, RESPONSE_SUFFIX));
+        let parse_result = ParseResult::Periodic(Box::new(ParseResult::Value("500 kč ročně".into(), Money::from_units(500))), Period::Year);
         let response_row = test_bot.generate_result_row(&parse_result);
-        assert_eq!(response_row, format!("> 30k\n\n{}vic jak {} palety ({} baliku) dvoulitrovek {}\n\n", BranikBot::RESPONSE_PREFIX, (30000.0 / (12.0*8.0*3.0*test_bot.config.default_price)) as i32, (30000.0 / test_bot.config.default_price / 6.0) as i32, BranikBot::RESPONSE_SUFFIX));
-        let parse_result = ParseResult::Value("150k".to_string(), 150000.0);
+        assert_eq!(response_row, format!("> 500 kč ročně\n\n{}2 baliky {} To je 0,03 dvoulitrovky denně.\n\n",
+            RESPONSE_PREFIX, RESPONSE_SUFFIX));
+    }
+
+    #[tokio::test]
+    async fn test_foreign_value_result_row() {
+        let mut test_bot = memory_bot("foreign-value-result-row");
+        let today = Local::now().format("%d.%m.%Y");
+        test_bot.exchange_rates = ExchangeRates::read_rates(&format!("{} #1\nzemě|měna|množství|kód|kurz\nEMU|euro|1|EUR|25,000", today), 4);
+        let parse_result = ParseResult::ForeignValue("20 €".into(), Money::from_units(20), Currency::Eur);
+        assert!(test_bot.can_generate_result_row(&parse_result));
         let response_row = test_bot.generate_result_row(&parse_result);
-        assert_eq!(response_row, format!("> 150k\n\n{}vic jak {} palet ({} baliku) dvoulitrovek {}\n\n", BranikBot::RESPONSE_PREFIX, (150000.0 / (12.0*8.0*3.0*test_bot.config.default_price)) as i32, (150000.0 / test_bot.config.default_price / 6.0) as i32, BranikBot::RESPONSE_SUFFIX));
+        assert_eq!(response_row, format!("> 20 €\n\n20,00 EUR je zhruba 500,00 Kč. {}2 baliky {}\n\n",
+            RESPONSE_PREFIX, RESPONSE_SUFFIX));
+        let parse_result = ParseResult::ForeignValue("$20".into(), Money::from_units(20), Currency::Usd);
+        assert!(!test_bot.can_generate_result_row(&parse_result));
+    }
+
+    #[tokio::test]
+    async fn test_skip_low_confidence() {
+        let test_bot = memory_bot("skip-low-confidence");
+        let results = test_bot.parser.parse("Mam 4k televizi za 12k").unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(!test_bot.can_generate_result_row(&results[0]));
+        assert!(test_bot.can_generate_result_row(&results[1]));
+        // "litr" is money only next to a price word
+        let results = test_bot.parser.parse("Vypil jsem litr").unwrap();
+        assert!(!test_bot.can_generate_result_row(&results[0]));
+        let results = test_bot.parser.parse("Koupil jsem litr rumu za 300 kc").unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(!test_bot.can_generate_result_row(&results[0]));
+        assert!(test_bot.can_generate_result_row(&results[1]));
+        assert_eq!(Some(1), test_bot.generate_response("Koupil jsem litr rumu za 300 kc").map(|r| r.matches("> ").count()));
+    }
+
+    #[tokio::test]
+    async fn test_quantity_result_row() {
+        let test_bot = memory_bot("quantity-result-row");
+        test_bot.branik_price.set(Money::from_halere(3990));
+        let parse_result = ParseResult::Quantity("24 Braníků".into(), 24.0, Packaging::Pet);
+        assert!(test_bot.can_generate_result_row(&parse_result));
+        let response_row = test_bot.generate_result_row(&parse_result);
+        assert_eq!(response_row, "> 24 Braníků\n\nZa 24 dvoulitrovek Branika ve sleve das aktualne 957,60 Kč.\n\n");
+        let parse_result = ParseResult::Quantity("bedna branika".into(), 1.0, Packaging::Pack);
+        let response_row = test_bot.generate_result_row(&parse_result);
+        assert_eq!(response_row, "> bedna branika\n\nZa 6 dvoulitrovek Branika ve sleve das aktualne 239,40 Kč.\n\n");
+        let parse_result = ParseResult::Quantity("paleta braniku".into(), 1.0, Packaging::Palett);
+        let response_row = test_bot.generate_result_row(&parse_result);
+        assert_eq!(response_row, "> paleta braniku\n\nZa 288 dvoulitrovek Branika ve sleve das aktualne 11 491,20 Kč.\n\n");
+        let parse_result = ParseResult::Quantity("3 litry braniku".into(), 3.0, Packaging::Liter);
+        let response_row = test_bot.generate_result_row(&parse_result);
+        assert_eq!(response_row, "> 3 litry braniku\n\nZa 2 dvoulitrovky Branika ve sleve das aktualne 79,80 Kč.\n\n");
+        let parse_result = ParseResult::Quantity("1e30 braniku".into(), 1e30, Packaging::Pet);
+        assert!(!test_bot.can_generate_result_row(&parse_result));
+    }
+
+    #[tokio::test]
+    async fn test_goods_result_row() {
+        let test_bot = memory_bot("goods-result-row");
+        *test_bot.goods.write().unwrap() = GoodsCatalog::built_in("cs").select(&["rohlik".to_string(), "kebab".to_string(), "fabia".to_string()]);
+        let parse_result = ParseResult::Value("500 kc".into(), Money::from_units(500));
+        let response_row = test_bot.generate_result_row(&parse_result);
+        assert_eq!(response_row, format!("> 500 kc\n\n{}2 baliky {} Nebo na 128 rohliku, 3 kebaby.\n\n",
+            RESPONSE_PREFIX, RESPONSE_SUFFIX));
+        let parse_result = ParseResult::Value("20 kc".into(), Money::from_units(20));
+        let response_row = test_bot.generate_result_row(&parse_result);
+        assert_eq!(response_row, "> 20 kc\n\nJe mi to lito, ale to neni ani na jednu dvoulitrovku Branika ve sleve. Stacilo by to aspon na 5 rohliku.\n\n");
+    }
+
+    #[test]
+    fn test_built_in_text() {
+        let table = [("cs", "dvoulitrovka"), ("en", "2L bottle")];
+        assert_eq!("2L bottle", built_in_text(&table, "en", "packaging"));
+        assert_eq!("dvoulitrovka", built_in_text(&table, "de", "packaging"));
+    }
+
+    #[test]
+    fn test_is_ignored_author() {
+        let test_bot = memory_bot("ignored-author");
+        assert!(test_bot.is_ignored_author("fabia-bot"));
+        assert!(test_bot.is_ignored_author("automoderator"));
+        assert!(!test_bot.is_ignored_author("Talbot"));
+        assert!(!test_bot.is_ignored_author("RemindMeBot"));
     }
 
     #[tokio::test]
     async fn test_branik_price_row() {
-        let test_bot = BranikBot::respawn().await;
+        let test_bot = memory_bot("branik-price-row");
         let result = test_bot.generate_keyword_result_row();
         assert_eq!(result, format!("Dvoulitrovka Branika ve sleve aktualne stoji 39,90 Kč"));
     }
+
+    #[tokio::test]
+    async fn test_english_templates() {
+        let mut test_bot = memory_bot("english-templates");
+        test_bot.templates = Templates::built_in("en");
+        test_bot.packaging = PackagingModel::built_in("en");
+        test_bot.packaging.set_words(test_bot.templates.get("and"), test_bot.templates.get("to"));
+        *test_bot.goods.write().unwrap() = GoodsCatalog::built_in("en").select(&["rohlik".to_string(), "kebab".to_string()]);
+        let parse_result = ParseResult::Range("3-10k".into(), Money::from_units(3000), Money::from_units(10000));
+        assert_eq!(test_bot.generate_result_row(&parse_result),
+            "> 3-10k\n\nThat would buy 12 packs and 3 2L bottles to 2 layers and 9 packs of discounted Branik!\n\n");
+        let parse_result = ParseResult::Value("500 kc".into(), Money::from_units(500));
+        assert_eq!(test_bot.generate_result_row(&parse_result),
+            "> 500 kc\n\nThat would buy 2 packs of discounted Branik! Or 128 bread rolls, 3 kebabs.\n\n");
+        let result = test_bot.generate_keyword_result_row();
+        assert_eq!(result, format!("A 2L bottle of discounted Branik currently costs 39,90 CZK ({})", Local::now().format("%d.%m.%Y")));
+        let message = test_bot.generate_message_for_results(&[ParseResult::Value("20 kc".into(), Money::from_units(20))]);
+        assert!(message.starts_with("> 20 kc\n\nSorry, that's not even enough"));
+        assert!(message.ends_with("QA at r/branicek)"));
+    }
+
+    // bot replying to the in-memory reddit
+    fn memory_bot(name: &str) -> BranikBot<MemoryReddit> {
+        let data_dir = std::env::temp_dir().join(format!("branik-bot-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+        let mut config = Config::create_default_config();
+        config.data_dir = data_dir.to_string_lossy().to_string();
+        config.post_response = true;
+        // no scraping in the tests
+        config.price_sources = vec![];
+        config.price_history_path = config.data_path("price_history.txt");
+        config.price_quarantine_path = config.data_path("price_quarantine.txt");
+        // built-in tables instead of the files of a running bot
+        config.exchange_rates_path = config.data_path("exchange_rates.txt");
+        config.packaging_path = config.data_path("packaging.txt");
+        config.goods_path = config.data_path("goods.txt");
+        let reddit = MemoryReddit::new(&config.user_name);
+        BranikBot::new(config, reddit)
+    }
+
+    #[tokio::test]
+    async fn test_run_replies_to_new_comments() {
+        let mut test_bot = memory_bot("run");
+        test_bot.reddit.add_comment("c1", "pepa", "p1", "Dal jsem za to 500 kc");
+        test_bot.reddit.add_comment("c2", "jarda", "p1", "Nic tu neni");
+        test_bot.reddit.add_comment("c3", "fabia-bot", "p2", "500 kc je 0,001 Fabie");
+        test_bot.reddit.add_comment("c4", "USER_NAME", "p2", "Stalo to 500 kc");
+        test_bot.read_and_respond().await;
+        let replies = test_bot.reddit.replies();
+        assert_eq!(1, replies.len());
+        assert_eq!("t1_c1", replies[0].0);
+        assert!(replies[0].1.starts_with("> Dal jsem za to 500 kc\n\nTo by stacilo na "));
+        // already read comments are skipped
+        test_bot.read_and_respond().await;
+        assert_eq!(1, test_bot.reddit.replies().len());
+        test_bot.reddit.add_comment("c5", "pepa", "p2", "A tohle stalo 100 kc");
+        test_bot.read_and_respond().await;
+        let replies = test_bot.reddit.replies();
+        assert_eq!(2, replies.len());
+        assert_eq!("t1_c5", replies[1].0);
+    }
+
+    #[tokio::test]
+    async fn test_comments_per_post_limit() {
+        let mut test_bot = memory_bot("limit");
+        test_bot.config.comments_per_post_limit = 2;
+        test_bot.reddit.add_comment("c1", "pepa", "p1", "Stalo to 100 kc");
+        test_bot.reddit.add_comment("c2", "jarda", "p1", "Mne to stalo 200 kc");
+        test_bot.read_and_respond().await;
+        assert_eq!(2, test_bot.reddit.replies().len());
+        // the bot's replies from the earlier reads count towards the limit
+        test_bot.reddit.add_comment("c3", "franta", "p1", "A mne 300 kc");
+        test_bot.reddit.add_comment("c4", "pepa", "p2", "Tady to stalo 400 kc");
+        test_bot.read_and_respond().await;
+        let parents: Vec<String> = test_bot.reddit.replies().into_iter().map(|(parent, _)| parent).collect();
+        assert_eq!(vec!["t1_c2", "t1_c1", "t1_c4"], parents);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_doesnt_post() {
+        let mut test_bot = memory_bot("dry-run");
+        test_bot.config.post_response = false;
+        test_bot.reddit.add_comment("c1", "pepa", "p1", "Stalo to 100 kc");
+        test_bot.read_and_respond().await;
+        assert!(test_bot.reddit.replies().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_against_fake_reddit() {
+        let fake = FakeReddit::start().await;
+        fake.add_comment("c1", "pepa", "p1", "Dal jsem za to 500 kc");
+        fake.add_comment("c2", "jarda", "p1", "Nic tu neni");
+        fake.add_comment("c3", "franta", "p2", "Kolik stoji branik ted");
+        let mut config = memory_bot("fake-reddit").config;
+        config.reddit_url = fake.url.clone();
+        config.reddit_oauth_url = fake.url.clone();
+        config.subreddit = "czech".to_string();
+        let reddit = HttpReddit::connect(&config).await;
+        let mut test_bot = BranikBot::new(config, reddit);
+        test_bot.read_and_respond().await;
+        let expected = vec![
+            ("t1_c3".to_string(), test_bot.generate_response("Kolik stoji branik ted").unwrap()),
+            ("t1_c1".to_string(), test_bot.generate_response("Dal jsem za to 500 kc").unwrap())
+        ];
+        assert_eq!(expected, fake.replies());
+        assert_eq!(vec![
+            "POST /api/v1/access_token",
+            "GET /r/czech/comments/.json?limit=25",
+            "GET /user/USER_NAME/comments/.json?t=day",
+            "POST /api/comment",
+            "POST /api/comment"
+        ], fake.state().requests);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refresh_goods_prices() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        // answers every request with the page of a kebab
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/kebab", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let page = r#"<script type="application/ld+json">{"@type": "Offer", "price": "129.90", "priceCurrency": "CZK"}</script>"#;
+            while let Ok((mut connection, _)) = listener.accept().await {
+                let _ = connection.read(&mut [0; 4096]).await;
+                let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", page.len(), page);
+                let _ = connection.write_all(response.as_bytes()).await;
+            }
+        });
+        let test_bot = memory_bot("refresh-goods");
+        let mut goods = GoodsCatalog::built_in("cs").select(&["kebab".to_string()]);
+        goods[0].price_url = Some(url);
+        *test_bot.goods.write().unwrap() = goods;
+        let task = test_bot.spawn_refresh(RefreshSchedule { interval: Some(Duration::from_millis(50)), daily_times: vec![] }).unwrap();
+        let kebab_price = || test_bot.goods.read().unwrap()[0].price;
+        // waits for the refresh, a slow machine gets a few seconds
+        for _ in 0..500 {
+            if kebab_price() == Money::from_halere(12990) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        task.abort();
+        server.abort();
+        assert_eq!(Money::from_halere(12990), kebab_price());
+    }
+
+    #[tokio::test]
+    async fn test_last_known_price() {
+        let test_bot = memory_bot("last-price");
+        test_bot.update_price().await;
+        assert_eq!(Money::from_halere(3990), test_bot.branik_price.price());
+        assert_eq!(None, test_bot.branik_price.current().record);
+        let quote = PriceQuote {
+            price: Money::from_halere(3490),
+            store: Some("Penny".to_string()),
+            source: "kupi.cz".to_string(),
+            url: "https://www.kupi.cz/branik".to_string(),
+            valid_until: None
+        };
+        test_bot.branik_price.history().record(&quote, Local::now().fixed_offset());
+        // the recorded price is used instead of the default one, also after the restart
+        test_bot.update_price().await;
+        assert_eq!(Money::from_halere(3490), test_bot.branik_price.price());
+        let restarted = BranikBot::new(test_bot.config, MemoryReddit::new("USER_NAME"));
+        assert_eq!(Money::from_halere(3490), restarted.branik_price.price());
+        assert_eq!(Some("Penny".to_string()), restarted.branik_price.current().record.unwrap().quote.store);
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let test_bot = memory_bot("replay");
+        let comment = |id: &str, author: &str, post_id: &str, body: &str| Comment {
+            id: id.to_string(),
+            name: format!("t1_{}", id),
+            author: author.to_string(),
+            body: body.to_string(),
+            link_id: format!("t3_{}", post_id),
+            ..Default::default()
+        };
+        let comments = [
+            comment("c1", "pepa", "p1", "Stalo to 100 kc"),
+            comment("c2", "USER_NAME", "p1", "Stalo to 100 kc"),
+            comment("c3", "fabia-bot", "p1", "Stalo to 100 kc"),
+            comment("c4", "jarda", "p1", "Nic"),
+            comment("c5", "jarda", "p1", "Mam 4k televizi"),
+            comment("c6", "jarda", "p1", "Mne 200 kc"),
+            comment("c7", "franta", "p1", "A mne 300 kc"),
+            comment("c8", "franta", "p1", "Zas 300 kc"),
+            comment("c9", "franta", "p2", "Tady 300 kc")
+        ];
+        let outcomes = test_bot.replay(&comments);
+        let skipped: Vec<Option<SkipReason>> = outcomes.iter().map(|o| o.clone().err()).collect();
+        assert_eq!(vec![None, Some(SkipReason::OwnComment), Some(SkipReason::IgnoredAuthor), Some(SkipReason::NothingFound),
+            Some(SkipReason::NothingToReply), None, None, Some(SkipReason::PostLimit(3)), None], skipped);
+        assert_eq!(&test_bot.generate_response("Stalo to 100 kc").unwrap(), outcomes[0].as_ref().unwrap());
+    }
 }
@@ -0,0 +1,230 @@
+use std::fs;
+
+use super::{built_in_text, plural::{Case, Noun}};
+
+// Packaging hierarchy the amounts are expressed in, read from a file with a tier per line, i.e.
+//
+// dvoulitrovka|dvoulitrovka|dvoulitrovku|dvoulitrovky|dvoulitrovek|dvoulitrovky|1
+// balik|balik|balik|baliky|baliku|baliku|6
+//
+// id|name for 1|name for 1 in accusative|name for 2 - 4|name for 5 and more|name for decimals|dvoulitrovky in the tier
+//
+// the ids are the same in every language, only the names are translated
+pub struct PackagingModel {
+    // ordered from the smallest, the first one is a single dvoulitrovka
+    tiers: Vec<Tier>,
+    // words joining the tiers, from the reply templates - "1 paleta a 1 vrstva", "1 až 3 dvoulitrovky"
+    and: String,
+    to: String
+}
+
+struct Tier {
+    id: String,
+    name: Noun,
+    size: u64
+}
+
+// dvoulitrovky split into the tiers - count for tier index, the biggest tier first
+pub struct BranikAmount {
+    parts: Vec<(usize, u64)>
+}
+
+impl BranikAmount {
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+}
+
+impl PackagingModel {
+    // 16 packs in a layer, 3 layers on a palett, 33 paletts in a truck and 52 in a train wagon,
+    // the names are in the language of the reply templates
+    const BUILT_IN: [(&'static str, &'static str); 3] = [
+        ("cs", "dvoulitrovka|dvoulitrovka|dvoulitrovku|dvoulitrovky|dvoulitrovek|dvoulitrovky|1
+balik|balik|balik|baliky|baliku|baliku|6
+vrstva|vrstva|vrstvu|vrstvy|vrstev|vrstvy|96
+paleta|paleta|paletu|palety|palet|palety|288
+kamion|kamion|kamion|kamiony|kamionu|kamionu|9504
+vagon|vagon|vagon|vagony|vagonu|vagonu|14976"),
+        ("sk", "dvoulitrovka|dvojlitrovka|dvojlitrovku|dvojlitrovky|dvojlitroviek|dvojlitrovky|1
+balik|balík|balík|balíky|balíkov|balíka|6
+vrstva|vrstva|vrstvu|vrstvy|vrstiev|vrstvy|96
+paleta|paleta|paletu|palety|paliet|palety|288
+kamion|kamión|kamión|kamióny|kamiónov|kamióna|9504
+vagon|vagón|vagón|vagóny|vagónov|vagóna|14976"),
+        ("en", "dvoulitrovka|2L bottle|2L bottle|2L bottles|2L bottles|2L bottles|1
+balik|pack|pack|packs|packs|packs|6
+vrstva|layer|layer|layers|layers|layers|96
+paleta|pallet|pallet|pallets|pallets|pallets|288
+kamion|truck|truck|trucks|trucks|trucks|9504
+vagon|train wagon|train wagon|train wagons|train wagons|train wagons|14976")
+    ];
+    // tiers the parsed quantities are converted with - "3 baliky", "2 palety"
+    pub const PACK: &'static str = "balik";
+    pub const PALETT: &'static str = "paleta";
+
+    // the file has to be in the language of the reply templates, built-in tiers are in the same language
+    pub fn load(path: &str, language: &str) -> PackagingModel {
+        match fs::read_to_string(path) {
+            Err(_) => {
+                println!("Failed to load packaging from {}, using default one", path);
+                Self::built_in(language)
+            },
+            Ok(text) => Self::read_tiers(&text, language)
+        }
+    }
+
+    pub fn built_in(language: &str) -> PackagingModel {
+        let text = built_in_text(&Self::BUILT_IN, language, "packaging");
+        Self::read_tiers(text, language)
+    }
+
+    pub fn read_tiers(tiers_str: &str, language: &str) -> PackagingModel {
+        let mut tiers = vec![];
+        for line in tiers_str.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let columns: Vec<&str> = line.split('|').map(|c| c.trim()).collect();
+            let [id, ref names @ .., size] = columns[..] else {
+                println!("Invalid packaging line '{}'", line);
+                continue;
+            };
+            match (Noun::from_columns(names), size.parse::<u64>()) {
+                (Some(name), Ok(size)) if size > 0 => tiers.push(Tier { id: id.to_string(), name, size }),
+                _ => println!("Invalid packaging line '{}'", line)
+            }
+        }
+        tiers.sort_by_key(|t| t.size);
+        if tiers.first().map(|t| t.size) != Some(1) {
+            println!("Packaging has to contain single dvoulitrovka, using default one");
+            return Self::built_in(language);
+        }
+        if let Some(id) = [Self::PACK, Self::PALETT].into_iter().find(|id| !tiers.iter().any(|t| t.id == *id)) {
+            println!("Packaging has to contain tier '{}', using default one", id);
+            return Self::built_in(language);
+        }
+        PackagingModel { tiers, and: "a".to_string(), to: "až".to_string() }
+    }
+
+    pub fn set_words(&mut self, and: &str, to: &str) {
+        self.and = and.to_string();
+        self.to = to.to_string();
+    }
+
+    // dvoulitrovky in the tier with given id - "balik", "paleta"...
+    pub fn size_of(&self, id: &str) -> Option<u64> {
+        self.tiers.iter().find(|t| t.id == id).map(|t| t.size)
+    }
+
+    // the biggest tier which fits the amount and the rest in one of the next two smaller tiers - "1 kamion a 2 palety",
+    // smaller remainder is dropped
+    pub fn amount(&self, bottles: u64) -> BranikAmount {
+        let mut parts = vec![];
+        let mut rest = bottles;
+        for (index, tier) in self.tiers.iter().enumerate().rev() {
+            let count = rest / tier.size;
+            if count > 0 {
+                parts.push((index, count));
+                rest -= count * tier.size;
+            }
+            if !parts.is_empty() && (parts.len() == 2 || index + 1 < parts[0].0) {
+                break;
+            }
+        }
+        BranikAmount { parts }
+    }
+
+    // amount of single dvoulitrovky - "24 dvoulitrovek"
+    pub fn bottles_text(&self, bottles: u64, case: Case) -> String {
+        self.tiers[0].name.text(bottles, case)
+    }
+
+    // part of single dvoulitrovka - "0,25 dvoulitrovky"
+    pub fn decimal_bottles_text(&self, bottles: f64, case: Case) -> String {
+        self.tiers[0].name.decimal_text(bottles, case)
+    }
+
+    pub fn text(&self, amount: &BranikAmount, case: Case) -> String {
+        if amount.is_empty() {
+            return self.bottles_text(0, case);
+        }
+        amount.parts.iter()
+            .map(|(tier, count)| self.tiers[*tier].name.text(*count, case))
+            .collect::<Vec<String>>()
+            .join(&format!(" {} ", self.and))
+    }
+
+    // same tier on both ends is named only once - "1 až 3 dvoulitrovky", "3 dvoulitrovky až 2 palety"
+    pub fn range_text(&self, from: &BranikAmount, to: &BranikAmount, case: Case) -> String {
+        match (&from.parts[..], &to.parts[..]) {
+            ([], [(0, _)]) => format!("0 {} {}", self.to, self.text(to, case)),
+            ([(from_tier, from_count)], [(to_tier, _)]) if from_tier == to_tier => format!("{} {} {}", from_count, self.to, self.text(to, case)),
+            _ => format!("{} {} {}", self.text(from, case), self.to, self.text(to, case))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_text() {
+        let model = PackagingModel::built_in("cs");
+        assert_eq!("0 dvoulitrovek", model.text(&model.amount(0), Case::Accusative));
+        assert_eq!("1 dvoulitrovku", model.text(&model.amount(1), Case::Accusative));
+        assert_eq!("3 dvoulitrovky", model.text(&model.amount(3), Case::Accusative));
+        assert_eq!("1 balik a 1 dvoulitrovku", model.text(&model.amount(7), Case::Accusative));
+        assert_eq!("2 palety", model.text(&model.amount(576), Case::Accusative));
+        assert_eq!("3 palety a 2 vrstvy", model.text(&model.amount(1127), Case::Accusative));
+        assert_eq!("1 kamion a 2 palety", model.text(&model.amount(9504 + 2 * 288 + 50), Case::Accusative));
+        // kamion is skipped, the rest is in paletts
+        assert_eq!("1 vagon a 2 palety", model.text(&model.amount(14976 + 576), Case::Accusative));
+        // the rest smaller than the next tier is dropped
+        assert_eq!("1 paletu", model.text(&model.amount(288 + 5), Case::Accusative));
+        assert_eq!("1 paleta a 1 vrstva", model.text(&model.amount(288 + 96), Case::Nominative));
+    }
+
+    #[test]
+    fn test_range_text() {
+        let model = PackagingModel::built_in("cs");
+        assert_eq!("0 až 1 dvoulitrovku", model.range_text(&model.amount(0), &model.amount(1), Case::Accusative));
+        assert_eq!("2 až 5 dvoulitrovek", model.range_text(&model.amount(2), &model.amount(5), Case::Accusative));
+        assert_eq!("1 až 2 palety", model.range_text(&model.amount(288), &model.amount(576), Case::Accusative));
+        assert_eq!("3 dvoulitrovky až 1 balik", model.range_text(&model.amount(3), &model.amount(6), Case::Accusative));
+        let mut model = PackagingModel::built_in("cs");
+        model.set_words("and", "to");
+        assert_eq!("1 paleta and 1 vrstva to 2 palety", model.range_text(&model.amount(384), &model.amount(576), Case::Nominative));
+    }
+
+    #[test]
+    fn test_read_tiers() {
+        let model = PackagingModel::read_tiers("# comment\nbalik|balik|balik|baliky|baliku|baliku|6\ndvoulitrovka|dvoulitrovka|dvoulitrovku|dvoulitrovky|dvoulitrovek|dvoulitrovky|1\nspatne|radek\npaleta|paleta|paletu|palety|palet|palety|300", "cs");
+        assert_eq!(Some(6), model.size_of("balik"));
+        assert_eq!(Some(300), model.size_of("paleta"));
+        assert_eq!(None, model.size_of("vrstva"));
+        assert_eq!("3 baliky", model.text(&model.amount(18), Case::Accusative));
+        // single dvoulitrovka is required
+        let model = PackagingModel::read_tiers("balik|balik|balik|baliky|baliku|baliku|6", "cs");
+        assert_eq!(Some(288), model.size_of("paleta"));
+        // tiers of the parsed quantities are required
+        let model = PackagingModel::read_tiers("dvoulitrovka|dvoulitrovka|dvoulitrovku|dvoulitrovky|dvoulitrovek|dvoulitrovky|1\nbalik|balik|balik|baliky|baliku|baliku|12", "cs");
+        assert_eq!(Some(6), model.size_of("balik"));
+        assert_eq!(Some(288), model.size_of("paleta"));
+    }
+
+    #[test]
+    fn test_built_in() {
+        for (language, _) in PackagingModel::BUILT_IN {
+            let model = PackagingModel::built_in(language);
+            assert_eq!(6, model.tiers.len(), "invalid built-in packaging for {}", language);
+        }
+        let mut model = PackagingModel::built_in("en");
+        model.set_words("and", "to");
+        assert_eq!("1 pack and 1 2L bottle", model.text(&model.amount(7), Case::Accusative));
+        assert_eq!("3 2L bottles to 1 pack", model.range_text(&model.amount(3), &model.amount(6), Case::Accusative));
+        let model = PackagingModel::built_in("sk");
+        assert_eq!("3 palety a 2 vrstvy", model.text(&model.amount(1127), Case::Accusative));
+        assert_eq!("5 dvojlitroviek", model.text(&model.amount(5), Case::Accusative));
+        // unknown language falls back to czech
+        let model = PackagingModel::built_in("de");
+        assert_eq!("1 balik", model.text(&model.amount(6), Case::Accusative));
+    }
+}
@@ -0,0 +1,122 @@
+// Noun forms for counts in reply text - "1 dvoulitrovku", "3 dvoulitrovky", "5 dvoulitrovek", "1,5 dvoulitrovky".
+// Slovak and English names fit the same czech forms.
+// Only inanimate nouns are expected, their plural is the same in both supported cases.
+
+// case of the counted noun, i.e. "To je 1 paleta" vs "To by stacilo na 1 paletu"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    Nominative,
+    Accusative
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Noun {
+    // singular for the nominative and the accusative case
+    one: [String; 2],
+    // 2 - 4
+    few: String,
+    // 0 and 5 and more, including 11 - 14 and 21, 22... - "22 dvoulitrovek"
+    many: String,
+    // any decimal count, genitive singular - "0,5 palety"
+    fraction: String
+}
+
+impl Noun {
+    pub fn new(one: &str, one_accusative: &str, few: &str, many: &str, fraction: &str) -> Noun {
+        Noun {
+            one: [one.to_string(), one_accusative.to_string()],
+            few: few.to_string(),
+            many: many.to_string(),
+            fraction: fraction.to_string()
+        }
+    }
+
+    // forms in the order of the config columns - 1|1 accusative|2 - 4|5 and more|decimal
+    pub fn from_columns(columns: &[&str]) -> Option<Noun> {
+        match columns {
+            [one, one_accusative, few, many, fraction] => Some(Noun::new(one, one_accusative, few, many, fraction)),
+            _ => None
+        }
+    }
+
+    pub fn form(&self, count: u64, case: Case) -> &str {
+        match (count, case) {
+            (1, Case::Nominative) => &self.one[0],
+            (1, Case::Accusative) => &self.one[1],
+            (2..=4, _) => &self.few,
+            _ => &self.many
+        }
+    }
+
+    // "3 palety"
+    pub fn text(&self, count: u64, case: Case) -> String {
+        format!("{} {}", count, self.form(count, case))
+    }
+
+    // count rounded to two decimals, whole counts are the same as in text - "0,25 dvoulitrovky", "2 dvoulitrovky"
+    pub fn decimal_text(&self, count: f64, case: Case) -> String {
+        let rounded = (count * 100.0).round() / 100.0;
+        if rounded.fract() == 0.0 {
+            return self.text(rounded as u64, case);
+        }
+        let count = format!("{:.2}", rounded).trim_end_matches('0').replace('.', ",");
+        format!("{} {}", count, self.fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dvoulitrovka() -> Noun {
+        Noun::new("dvoulitrovka", "dvoulitrovku", "dvoulitrovky", "dvoulitrovek", "dvoulitrovky")
+    }
+
+    #[test]
+    fn test_text() {
+        let noun = dvoulitrovka();
+        let expected = [
+            (0, "0 dvoulitrovek", "0 dvoulitrovek"),
+            (1, "1 dvoulitrovka", "1 dvoulitrovku"),
+            (2, "2 dvoulitrovky", "2 dvoulitrovky"),
+            (3, "3 dvoulitrovky", "3 dvoulitrovky"),
+            (4, "4 dvoulitrovky", "4 dvoulitrovky"),
+            (5, "5 dvoulitrovek", "5 dvoulitrovek"),
+            (11, "11 dvoulitrovek", "11 dvoulitrovek"),
+            (12, "12 dvoulitrovek", "12 dvoulitrovek"),
+            (13, "13 dvoulitrovek", "13 dvoulitrovek"),
+            (14, "14 dvoulitrovek", "14 dvoulitrovek"),
+            (21, "21 dvoulitrovek", "21 dvoulitrovek"),
+            (22, "22 dvoulitrovek", "22 dvoulitrovek"),
+            (101, "101 dvoulitrovek", "101 dvoulitrovek"),
+            (1000000, "1000000 dvoulitrovek", "1000000 dvoulitrovek")
+        ];
+        for (count, nominative, accusative) in expected {
+            assert_eq!(nominative, noun.text(count, Case::Nominative));
+            assert_eq!(accusative, noun.text(count, Case::Accusative));
+        }
+    }
+
+    #[test]
+    fn test_decimal_text() {
+        let noun = dvoulitrovka();
+        assert_eq!("0,5 dvoulitrovky", noun.decimal_text(0.5, Case::Nominative));
+        assert_eq!("1,5 dvoulitrovky", noun.decimal_text(1.5, Case::Accusative));
+        assert_eq!("2,25 dvoulitrovky", noun.decimal_text(2.25, Case::Nominative));
+        assert_eq!("0,03 dvoulitrovky", noun.decimal_text(0.034, Case::Nominative));
+        // whole counts after rounding
+        assert_eq!("1 dvoulitrovka", noun.decimal_text(1.0, Case::Nominative));
+        assert_eq!("1 dvoulitrovku", noun.decimal_text(0.999, Case::Accusative));
+        assert_eq!("5 dvoulitrovek", noun.decimal_text(5.0, Case::Accusative));
+        assert_eq!("0 dvoulitrovek", noun.decimal_text(0.001, Case::Nominative));
+    }
+
+    #[test]
+    fn test_from_columns() {
+        let noun = Noun::from_columns(&["paleta", "paletu", "palety", "palet", "palety"]).unwrap();
+        assert_eq!("1 paleta", noun.text(1, Case::Nominative));
+        assert_eq!("1 paletu", noun.text(1, Case::Accusative));
+        assert_eq!("1,5 palety", noun.decimal_text(1.5, Case::Accusative));
+        assert_eq!(None, Noun::from_columns(&["paleta", "palety", "palet"]));
+    }
+}
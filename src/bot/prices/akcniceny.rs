@@ -0,0 +1,91 @@
+use chrono::NaiveDate;
+
+use super::{PriceError, PriceQuote, PriceSource, schema::Offer};
+
+// Product page on akcniceny.cz, "lowPrice" is the lowest price of the product in the current leaflets
+pub struct AkcniCeny {
+    url: String
+}
+
+impl AkcniCeny {
+    pub const BRANIK_URL: &'static str = "https://www.akcniceny.cz/akce/branik-pivo-vycepni-svetle-2-0l-pet/";
+
+    pub fn new(url: String) -> AkcniCeny {
+        AkcniCeny { url }
+    }
+}
+
+impl PriceSource for AkcniCeny {
+    fn name(&self) -> &str {
+        "akcniceny.cz"
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    // schema.org offers of the page, the aggregate is used only without offers of the stores, its "lowPrice"
+    // might come from an offer already ended
+    fn parse(&self, page: &str, today: NaiveDate) -> Result<Vec<PriceQuote>, PriceError> {
+        let offers = Offer::extract_czk(page)?;
+        let has_stores = offers.iter().any(|o| o.seller.is_some());
+        let lowest = offers.into_iter()
+            .filter(|o| o.seller.is_some() || !has_stores)
+            .filter(|o| o.is_available(today))
+            .min_by_key(|o| o.low_price)
+            .ok_or(PriceError::Page("No offer in stock and valid today".to_string()))?;
+        Ok(vec![PriceQuote {
+            price: lowest.low_price,
+            store: lowest.seller,
+            source: self.name().to_string(),
+            url: self.url.clone(),
+            valid_until: lowest.valid_through
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::money::Money;
+
+    use crate::bot::prices::schema::OfferError;
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let source = AkcniCeny::new(AkcniCeny::BRANIK_URL.to_string());
+        assert_eq!(Ok(vec![PriceQuote {
+            price: Money::from_halere(3990),
+            store: Some("Albert".to_string()),
+            source: "akcniceny.cz".to_string(),
+            url: AkcniCeny::BRANIK_URL.to_string(),
+            valid_until: Some(date(21))
+        }]), source.parse(include_str!("fixtures/akcniceny.html"), date(17)));
+        // the same offers in microdata, Tesco is sold out
+        let quotes = source.parse(include_str!("fixtures/akcniceny_microdata.html"), date(17)).unwrap();
+        assert_eq!((Money::from_halere(3990), Some("Albert")), (quotes[0].price, quotes[0].store.as_deref()));
+        assert_eq!(Err(PriceError::Offer(OfferError::NoOffer)), source.parse("<html><body>Nic</body></html>", date(17)));
+        assert_eq!(Err(PriceError::Offer(OfferError::InvalidPrice("zdarma".to_string()))),
+            source.parse(r#"<span itemscope itemtype="https://schema.org/AggregateOffer"><span itemprop="lowPrice" content="zdarma"></span></span>"#, date(17)));
+        assert!(source.parse(r#"<div itemscope itemtype="https://schema.org/Offer"><meta itemprop="price" content="39.90"><link itemprop="availability" href="https://schema.org/OutOfStock"></div>"#, date(17)).is_err());
+    }
+
+    #[test]
+    fn test_parse_current_offers() {
+        let source = AkcniCeny::new(AkcniCeny::BRANIK_URL.to_string());
+        let page = include_str!("fixtures/akcniceny.html");
+        let lowest = |today: NaiveDate| source.parse(page, today).map(|quotes| (quotes[0].price, quotes[0].store.clone()));
+        // Tesco from 14. 10. to 20. 10., Albert from 15. 10. to 21. 10., Kaufland from 16. 10. to 22. 10.
+        assert_eq!(Ok((Money::from_halere(4990), Some("Tesco".to_string()))), lowest(date(14)));
+        assert_eq!(Ok((Money::from_halere(3990), Some("Albert".to_string()))), lowest(date(15)));
+        assert_eq!(Ok((Money::from_halere(4290), Some("Kaufland".to_string()))), lowest(date(22)));
+        // the aggregate price of the ended offers is not used
+        assert_eq!(Err(PriceError::Page("No offer in stock and valid today".to_string())), lowest(date(23)));
+        assert!(lowest(date(13)).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+use std::{fs, io::Write};
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate};
+
+use crate::money::Money;
+
+use super::PriceQuote;
+
+// Braník prices found by the sources, appended to a file with a record per line, i.e.
+//
+// 2026-10-17T10:05:00+02:00|39.90|Albert|akcniceny.cz|https://www.akcniceny.cz/...|2026-10-21
+//
+// time of the fetch|price|store|source|url|last day of the sale, store and the last day are empty when unknown.
+// Only the latest records are kept, the file is rewritten with them when it grows over the limit.
+pub struct PriceHistory {
+    path: String,
+    // the oldest first
+    records: Vec<PriceRecord>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceRecord {
+    pub time: DateTime<FixedOffset>,
+    pub quote: PriceQuote
+}
+
+impl PriceRecord {
+    fn line(&self) -> String {
+        let valid_until = self.quote.valid_until.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default();
+        let column = |text: &str| text.replace('|', "/");
+        format!("{}|{}|{}|{}|{}|{}", self.time.to_rfc3339(), Self::price_column(self.quote.price),
+            column(self.quote.store.as_deref().unwrap_or("")), column(&self.quote.source), column(&self.quote.url), valid_until)
+    }
+
+    // "39.90", the display format has thousands separators
+    fn price_column(price: Money) -> String {
+        format!("{}.{:02}", price.halere() / 100, price.halere() % 100)
+    }
+
+    fn read_line(line: &str) -> Option<PriceRecord> {
+        let columns: Vec<&str> = line.split('|').map(|c| c.trim()).collect();
+        let [time, price, store, source, url, valid_until] = columns[..] else {
+            return None;
+        };
+        let valid_until = match valid_until {
+            "" => None,
+            date => Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?)
+        };
+        Some(PriceRecord {
+            time: DateTime::parse_from_rfc3339(time).ok()?,
+            quote: PriceQuote {
+                price: Money::parse(price).filter(|p| p.is_positive())?,
+                store: Some(store.to_string()).filter(|s| !s.is_empty()),
+                source: source.to_string(),
+                url: url.to_string(),
+                valid_until
+            }
+        })
+    }
+}
+
+impl PriceHistory {
+    // a few fetches a day for more than a year
+    const MAX_RECORDS: usize = 2000;
+
+    // missing file is an empty history
+    pub fn load(path: &str) -> PriceHistory {
+        match fs::read_to_string(path) {
+            Err(_) => PriceHistory { path: path.to_string(), records: vec![] },
+            Ok(text) => Self::read_history(path, &text)
+        }
+    }
+
+    pub fn read_history(path: &str, history_str: &str) -> PriceHistory {
+        let mut records = vec![];
+        for line in history_str.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            match PriceRecord::read_line(line) {
+                Some(record) => records.push(record),
+                None => println!("Invalid price history line '{}'", line)
+            }
+        }
+        records.sort_by_key(|r| r.time);
+        let mut history = PriceHistory { path: path.to_string(), records };
+        history.drop_oldest(Self::MAX_RECORDS);
+        history
+    }
+
+    // appended to the file right away, the record is kept in memory even when the write fails
+    pub fn record(&mut self, quote: &PriceQuote, time: DateTime<FixedOffset>) -> &PriceRecord {
+        let record = PriceRecord { time, quote: quote.clone() };
+        let open_file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path);
+        match open_file {
+            Err(e) => println!("Failed to open price history {}: {}", self.path, e),
+            Ok(mut file) => {
+                if let Err(e) = writeln!(file, "{}", record.line()) {
+                    println!("Failed to write price history {}: {}", self.path, e);
+                }
+            }
+        }
+        let is_full = self.records.len() >= Self::MAX_RECORDS;
+        if is_full {
+            self.drop_oldest(Self::MAX_RECORDS - 1);
+        }
+        let index = self.records.partition_point(|r| r.time <= time);
+        self.records.insert(index, record);
+        if is_full {
+            self.save();
+        }
+        &self.records[index]
+    }
+
+    fn drop_oldest(&mut self, max_records: usize) {
+        let count = self.records.len().saturating_sub(max_records);
+        self.records.drain(..count);
+    }
+
+    // the records not kept are removed from the file too
+    pub fn retain(&mut self, keep: impl Fn(&PriceRecord) -> bool) {
+        let count = self.records.len();
+        self.records.retain(keep);
+        if self.records.len() < count {
+            self.save();
+        }
+    }
+
+    // the whole file is replaced by the records in memory
+    fn save(&self) {
+        let lines: String = self.records.iter().map(|r| r.line() + "\n").collect();
+        if let Err(e) = fs::write(&self.path, lines) {
+            println!("Failed to write price history {}: {}", self.path, e);
+        }
+    }
+
+    // the last known good price
+    pub fn last(&self) -> Option<&PriceRecord> {
+        self.records.last()
+    }
+
+    // the last price unless it's older than the days
+    pub fn last_within(&self, now: DateTime<FixedOffset>, max_age_days: i64) -> Option<&PriceRecord> {
+        self.last().filter(|r| now.signed_duration_since(r.time) <= Duration::days(max_age_days))
+    }
+
+    // records fetched from the first to the last day including both, in the time zone of the record
+    pub fn between(&self, from: NaiveDate, to: NaiveDate) -> Vec<&PriceRecord> {
+        self.records.iter().filter(|r| (from..=to).contains(&r.time.date_naive())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn time(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(time).unwrap()
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    fn quote(price: i64, store: Option<&str>) -> PriceQuote {
+        PriceQuote {
+            price: Money::from_halere(price),
+            store: store.map(|s| s.to_string()),
+            source: "akcniceny.cz".to_string(),
+            url: "https://www.akcniceny.cz/branik".to_string(),
+            valid_until: None
+        }
+    }
+
+    #[test]
+    fn test_read_history() {
+        let history = PriceHistory::read_history("", "# comment
+2026-10-17T10:05:00+02:00|39.90|Albert|akcniceny.cz|https://www.akcniceny.cz/branik|2026-10-21
+2026-10-15T22:00:00+02:00|1239,90||kupi.cz|https://www.kupi.cz/branik|
+2026-10-16|39.90|Albert|akcniceny.cz|https://www.akcniceny.cz/branik|
+2026-10-16T10:00:00+02:00|0|Albert|akcniceny.cz|https://www.akcniceny.cz/branik|
+spatny radek");
+        assert_eq!(2, history.records.len());
+        let last = history.last().unwrap();
+        assert_eq!(time("2026-10-17T10:05:00+02:00"), last.time);
+        assert_eq!(PriceQuote { valid_until: Some(date("2026-10-21")), ..quote(3990, Some("Albert")) }, last.quote);
+        assert_eq!(None, history.records[0].quote.store);
+        assert_eq!(Money::from_halere(123990), history.records[0].quote.price);
+    }
+
+    #[test]
+    fn test_record_and_between() {
+        let path = env::temp_dir().join(format!("branik_price_history_{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = fs::remove_file(path);
+        let mut history = PriceHistory::load(path);
+        assert_eq!(None, history.last());
+        history.record(&quote(3990, Some("Albert")), time("2026-10-15T23:30:00+02:00"));
+        history.record(&PriceQuote { valid_until: Some(date("2026-10-22")), ..quote(123990, None) }, time("2026-10-17T08:00:00+02:00"));
+        history.record(&quote(4290, Some("Tesco")), time("2026-10-16T08:00:00+02:00"));
+        assert_eq!(Money::from_halere(123990), history.last().unwrap().quote.price);
+        // the records survive the restart
+        let history = PriceHistory::load(path);
+        let _ = fs::remove_file(path);
+        assert_eq!(3, history.records.len());
+        assert_eq!(Some(date("2026-10-22")), history.last().unwrap().quote.valid_until);
+        let prices = |records: Vec<&PriceRecord>| records.iter().map(|r| r.quote.price.halere()).collect::<Vec<i64>>();
+        assert_eq!(vec![3990, 4290], prices(history.between(date("2026-10-15"), date("2026-10-16"))));
+        assert_eq!(vec![4290, 123990], prices(history.between(date("2026-10-16"), date("2026-10-31"))));
+        assert!(history.between(date("2026-10-18"), date("2026-10-20")).is_empty());
+        assert_eq!(Some(Money::from_halere(123990)), history.last_within(time("2026-10-24T08:00:00+02:00"), 7).map(|r| r.quote.price));
+        assert_eq!(None, history.last_within(time("2026-10-24T08:00:01+02:00"), 7));
+    }
+
+    #[test]
+    fn test_max_records() {
+        let path = env::temp_dir().join(format!("branik_price_history_max_{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let start = time("2026-01-01T08:00:00+01:00");
+        let lines: String = (0..PriceHistory::MAX_RECORDS as i64 + 10)
+            .map(|i| PriceRecord { time: start + Duration::hours(i), quote: quote(3990 + i, None) }.line() + "\n")
+            .collect();
+        fs::write(path, lines).unwrap();
+        let mut history = PriceHistory::load(path);
+        assert_eq!(PriceHistory::MAX_RECORDS, history.records.len());
+        assert_eq!(Money::from_halere(4000), history.records[0].quote.price);
+        // the file is rewritten with the latest records
+        history.record(&quote(3490, None), time("2026-10-17T08:00:00+02:00"));
+        let history = PriceHistory::load(path);
+        let _ = fs::remove_file(path);
+        assert_eq!(PriceHistory::MAX_RECORDS, history.records.len());
+        assert_eq!(Money::from_halere(4001), history.records[0].quote.price);
+        assert_eq!(Money::from_halere(3490), history.last().unwrap().quote.price);
+    }
+}
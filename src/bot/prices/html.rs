@@ -0,0 +1,125 @@
+// Just enough of HTML to read the price pages, the markup of the shops is not expected to be valid
+
+// elements without the closing tag
+const VOID_ELEMENTS: [&str; 6] = ["meta", "link", "img", "br", "input", "hr"];
+
+// value of the attribute in the tag - attribute(r#"<span content="39.90">"#, "content") is "39.90"
+pub fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{}=\"", name);
+    let (index, _) = tag.match_indices(&pattern)
+        .find(|(i, _)| tag[..*i].ends_with(|c: char| c.is_whitespace()))?;
+    let start = index + pattern.len();
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+// attribute without value, i.e. "itemscope"
+pub fn has_flag(tag: &str, name: &str) -> bool {
+    tag.trim_start_matches('<').trim_end_matches('>').trim_end_matches('/')
+        .split_whitespace().skip(1)
+        .any(|a| a == name || a.starts_with(&format!("{}=", name)))
+}
+
+// opening tags in the part of the page with their start indexes, "<meta itemprop=\"price\" content=\"39.90\">"
+pub fn tags(page: &str, from: usize, to: usize) -> Vec<(usize, &str)> {
+    let mut tags = vec![];
+    let mut pos = from;
+    while let Some(start) = page[pos..to].find('<').map(|i| pos + i) {
+        let Some(end) = page[start..to].find('>').map(|i| start + i) else {
+            break;
+        };
+        if !page[start + 1..].starts_with(['/', '!']) {
+            tags.push((start, &page[start..=end]));
+        }
+        pos = end + 1;
+    }
+    tags
+}
+
+// "div" of "<div class=\"price\">" or "</div>"
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches(['<', '/']).chars().take_while(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+// index after the end of the element starting at the index, unclosed element ends with the page
+pub fn element_end(page: &str, start: usize) -> usize {
+    let Some(tag_end) = page[start..].find('>').map(|i| start + i) else {
+        return page.len();
+    };
+    let name = tag_name(&page[start..tag_end]);
+    if VOID_ELEMENTS.contains(&name.as_str()) || page[..tag_end].ends_with('/') {
+        return tag_end + 1;
+    }
+    let mut depth = 1;
+    let mut pos = tag_end + 1;
+    while let Some(next) = page.get(pos..).and_then(|rest| rest.find('<')).map(|i| pos + i) {
+        // an unclosed tag of a truncated page ends with the page
+        let end = page[next..].find('>').map(|i| next + i).unwrap_or(page.len());
+        if tag_name(page.get(next..end).unwrap_or_default()) == name {
+            match page[next + 1..].starts_with('/') {
+                true => depth -= 1,
+                false if !page[..end].ends_with('/') => depth += 1,
+                false => ()
+            }
+            if depth == 0 {
+                return (end + 1).min(page.len());
+            }
+        }
+        pos = end + 1;
+    }
+    page.len()
+}
+
+// text content of the element starting at the index, nested tags are dropped - "<b>39,90</b> Kč" is "39,90 Kč"
+pub fn element_text(page: &str, start: usize) -> Option<String> {
+    let content_start = start + page[start..].find('>')? + 1;
+    let end = element_end(page, start);
+    let content_end = page[..end].rfind("</").filter(|e| *e >= content_start).unwrap_or(end);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in page[content_start..content_end].chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => ()
+        }
+    }
+    Some(text.split_whitespace().collect::<Vec<&str>>().join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribute() {
+        assert_eq!(Some("39.90"), attribute(r#"<span itemprop="lowPrice" content="39.90">"#, "content"));
+        assert_eq!(None, attribute(r#"<span itemprop="lowPrice">"#, "content"));
+        // the whole attribute name is matched
+        assert_eq!(Some("49.90"), attribute(r#"<span data-price="39.90" price="49.90">"#, "price"));
+        assert!(has_flag("<div itemscope itemtype=\"https://schema.org/Offer\">", "itemscope"));
+        assert!(has_flag("<div itemscope>", "itemscope"));
+        assert!(!has_flag("<itemscope class=\"a\">", "itemscope"));
+    }
+
+    #[test]
+    fn test_elements() {
+        let page = r#"<div class="price"><strong>39,90</strong>&nbsp;Kč<br><div>dalsi</div></div><p>konec</p>"#;
+        assert_eq!(page.find("<p>").unwrap(), element_end(page, 0));
+        assert_eq!(Some("39,90&nbsp;Kčdalsi".to_string()), element_text(page, 0));
+        assert_eq!(Some("konec".to_string()), element_text(page, page.find("<p>").unwrap()));
+        assert_eq!(page.find("&nbsp;").unwrap(), element_end(page, page.find("<strong>").unwrap()));
+        let tags: Vec<&str> = tags(page, 0, page.len()).into_iter().map(|(_, t)| t).collect();
+        assert_eq!(vec!["<div class=\"price\">", "<strong>", "<br>", "<div>", "<p>"], tags);
+    }
+
+    #[test]
+    fn test_truncated_page() {
+        let page = "<div class=\"cena\">39,90 Kč <span>Žatec</span> <ž";
+        assert_eq!(page.len(), element_end(page, 0));
+        assert_eq!(Some("39,90 Kč Žatec".to_string()), element_text(page, 0));
+        let page = "<div>Cena 39,90 Kč</div";
+        assert_eq!(page.len(), element_end(page, 0));
+    }
+}
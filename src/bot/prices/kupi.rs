@@ -0,0 +1,80 @@
+use chrono::NaiveDate;
+
+use super::{PriceError, PriceQuote, PriceSource, html::element_text, parse_price_text};
+
+// Product page of a leaflet aggregator in the kupi.cz style, every discount lists the store and the price
+pub struct Kupi {
+    url: String
+}
+
+impl Kupi {
+    const SHOP_CLASS: &'static str = "discounts_shop_name";
+    const PRICE_CLASS: &'static str = "discount_price_value";
+
+    pub fn new(url: String) -> Kupi {
+        Kupi { url }
+    }
+
+    // text of the elements with the class, in the order of the page
+    fn texts(page: &str, class: &str) -> Vec<String> {
+        page.match_indices(&format!("class=\"{}", class))
+            .filter_map(|(i, _)| page[..i].rfind('<').and_then(|start| element_text(page, start)))
+            .collect()
+    }
+}
+
+impl PriceSource for Kupi {
+    fn name(&self) -> &str {
+        "kupi.cz"
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    // the discounts are listed without dates
+    fn parse(&self, page: &str, _today: NaiveDate) -> Result<Vec<PriceQuote>, PriceError> {
+        let shops = Self::texts(page, Self::SHOP_CLASS);
+        let prices = Self::texts(page, Self::PRICE_CLASS);
+        if prices.is_empty() {
+            return Err(PriceError::Page("Failed to find any discount".to_string()));
+        }
+        if shops.len() != prices.len() {
+            return Err(PriceError::Page(format!("Found {} stores for {} prices", shops.len(), prices.len())));
+        }
+        // discounts with unreadable price are left out
+        let quotes: Vec<PriceQuote> = shops.into_iter().zip(prices.iter())
+            .filter_map(|(shop, price)| Some(PriceQuote {
+                price: parse_price_text(price)?,
+                store: Some(shop),
+                source: self.name().to_string(),
+                url: self.url.clone(),
+                valid_until: None
+            }))
+            .collect();
+        match quotes.is_empty() {
+            true => Err(PriceError::Page(format!("Failed to parse any of the prices {:?}", prices))),
+            false => Ok(quotes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::money::Money;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let source = Kupi::new("https://www.kupi.cz/sleva/pivo-branik".to_string());
+        let quotes = source.parse(include_str!("fixtures/kupi.html"), today).unwrap();
+        let prices: Vec<(Option<&str>, Money)> = quotes.iter().map(|q| (q.store.as_deref(), q.price)).collect();
+        assert_eq!(vec![(Some("Penny"), Money::from_halere(3790)), (Some("Billa"), Money::from_halere(3990)),
+            (Some("Tesco"), Money::from_units(44))], prices);
+        assert!(quotes.iter().all(|q| q.source == "kupi.cz" && q.url == "https://www.kupi.cz/sleva/pivo-branik"));
+        assert!(source.parse("<html><body>Žádné slevy</body></html>", today).is_err());
+        assert!(source.parse(r#"<span class="discount_price_value">39,90 Kč</span>"#, today).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::{fmt, sync::OnceLock, time::Duration};
+
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
+use tokio::task::JoinSet;
+
+use crate::money::Money;
+
+use self::{akcniceny::AkcniCeny, kupi::Kupi, product_page::ProductPage, schema::OfferError};
+
+pub mod akcniceny;
+pub mod history;
+pub mod html;
+pub mod kupi;
+pub mod product_page;
+pub mod refresh;
+pub mod sanity;
+pub mod schema;
+
+// Page listing the price of a product, several stores might be listed on the page of an aggregator
+pub trait PriceSource: Send + Sync {
+    // "akcniceny.cz"
+    fn name(&self) -> &str;
+    fn url(&self) -> &str;
+    // offers sold out or not valid on the day are left out
+    fn parse(&self, page: &str, today: NaiveDate) -> Result<Vec<PriceQuote>, PriceError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceQuote {
+    pub price: Money,
+    // not every aggregator names the store with the lowest price
+    pub store: Option<String>,
+    pub source: String,
+    pub url: String,
+    // last day of the sale when the source tells
+    pub valid_until: Option<NaiveDate>
+}
+
+// why a source gave no price
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PriceError {
+    Fetch(String),
+    // structured schema.org data of the page
+    Offer(OfferError),
+    // the page doesn't look as expected
+    Page(String)
+}
+
+impl fmt::Display for PriceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceError::Fetch(e) => write!(f, "failed to load the page: {}", e),
+            PriceError::Offer(e) => write!(f, "failed to read the offer: {}", e),
+            PriceError::Page(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+impl From<OfferError> for PriceError {
+    fn from(error: OfferError) -> Self {
+        PriceError::Offer(error)
+    }
+}
+
+impl fmt::Display for PriceQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} Kč", self.price)?;
+        if let Some(store) = &self.store {
+            write!(f, " in {}", store)?;
+        }
+        if let Some(valid_until) = self.valid_until {
+            write!(f, " until {}", valid_until.format("%-d. %-m."))?;
+        }
+        write!(f, " from {} ({})", self.source, self.url)
+    }
+}
+
+// All sources of a product queried at once, the lowest price wins
+pub struct PriceSources {
+    sources: Vec<Box<dyn PriceSource>>
+}
+
+impl PriceSources {
+    pub fn new(sources: Vec<Box<dyn PriceSource>>) -> PriceSources {
+        PriceSources { sources }
+    }
+
+    // "akcniceny|<url>", "kupi|<url>" or "shop:<store name>|<url>" for a product page of a single chain
+    pub fn from_specs(specs: &[String]) -> Result<PriceSources, Vec<String>> {
+        let mut sources: Vec<Box<dyn PriceSource>> = vec![];
+        let mut errors = vec![];
+        for spec in specs {
+            let Some((kind, url)) = spec.split_once('|').map(|(k, u)| (k.trim(), u.trim().to_string())) else {
+                errors.push(format!("Invalid price source '{}', expected 'kind|url'", spec));
+                continue;
+            };
+            match kind.split_once(':') {
+                None if kind == "akcniceny" => sources.push(Box::new(AkcniCeny::new(url))),
+                None if kind == "kupi" => sources.push(Box::new(Kupi::new(url))),
+                Some(("shop", store)) if !store.trim().is_empty() => sources.push(Box::new(ProductPage::new(store.trim(), url))),
+                _ => errors.push(format!("Unknown price source '{}'", kind))
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(PriceSources { sources }),
+            false => Err(errors)
+        }
+    }
+
+    pub async fn lowest_price(&self) -> Option<PriceQuote> {
+        self.quotes().await.into_iter().min_by_key(|q| q.price)
+    }
+
+    // positive prices of all the sources, those failing are logged
+    pub async fn quotes(&self) -> Vec<PriceQuote> {
+        let pages = self.fetch_pages().await;
+        self.quotes_in_pages(&pages, Local::now().date_naive())
+    }
+
+    // pages in the order of the sources
+    async fn fetch_pages(&self) -> Vec<Result<String, PriceError>> {
+        let mut requests = JoinSet::new();
+        for (index, source) in self.sources.iter().enumerate() {
+            let url = source.url().to_string();
+            requests.spawn(async move { (index, fetch_page(shared_client(), &url).await) });
+        }
+        let mut pages: Vec<Result<String, PriceError>> = self.sources.iter().map(|_| Err(PriceError::Fetch("not fetched".to_string()))).collect();
+        while let Some(result) = requests.join_next().await {
+            if let Ok((index, page)) = result {
+                pages[index] = page;
+            }
+        }
+        pages
+    }
+
+    fn quotes_in_pages(&self, pages: &[Result<String, PriceError>], today: NaiveDate) -> Vec<PriceQuote> {
+        let mut quotes = vec![];
+        for (source, page) in self.sources.iter().zip(pages) {
+            match page.as_ref().map_err(|e| e.clone()).and_then(|p| source.parse(p, today)) {
+                Err(e) => println!("Failed to read price from {} ({}): {}", source.name(), source.url(), e),
+                Ok(found) => quotes.extend(found.into_iter().filter(|q| q.price.is_positive()))
+            }
+        }
+        quotes
+    }
+}
+
+// a stuck store page must not hold the price refresh
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// one client for all the sources, so the connections are reused between the refreshes
+fn shared_client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| http_client(CONNECT_TIMEOUT, REQUEST_TIMEOUT))
+}
+
+fn http_client(connect_timeout: Duration, request_timeout: Duration) -> Client {
+    Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .build()
+        .expect("Expected http client to be created")
+}
+
+pub async fn fetch_page(client: &Client, url: &str) -> Result<String, PriceError> {
+    let response = client.get(url).send().await.map_err(fetch_error)?;
+    response.text().await.map_err(|e| match e.is_timeout() {
+        true => fetch_error(e),
+        false => PriceError::Fetch(format!("not a text: {}", e))
+    })
+}
+
+fn fetch_error(error: reqwest::Error) -> PriceError {
+    match error.is_timeout() {
+        true => PriceError::Fetch(format!("timed out: {}", error)),
+        false => PriceError::Fetch(error.to_string())
+    }
+}
+
+// "39,90 Kč", "39.90", "39,-"
+fn parse_price_text(text: &str) -> Option<Money> {
+    let value = text.replace('\u{a0}', " ").replace("&nbsp;", " ").replace("Kč", "").replace(",-", "").trim().to_string();
+    Money::parse(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specs(specs: &[&str]) -> Vec<String> {
+        specs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_specs() {
+        let sources = PriceSources::from_specs(&specs(&["akcniceny|https://www.akcniceny.cz/branik", "kupi | https://www.kupi.cz/branik",
+            "shop:Albert|https://www.albert.cz/branik"])).unwrap();
+        let names: Vec<(&str, &str)> = sources.sources.iter().map(|s| (s.name(), s.url())).collect();
+        assert_eq!(vec![("akcniceny.cz", "https://www.akcniceny.cz/branik"), ("kupi.cz", "https://www.kupi.cz/branik"),
+            ("Albert", "https://www.albert.cz/branik")], names);
+        let errors = PriceSources::from_specs(&specs(&["letak|https://letak.cz", "shop:|https://shop.cz", "akcniceny"])).err().unwrap();
+        assert_eq!(3, errors.len());
+    }
+
+    #[test]
+    fn test_quotes_in_pages() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let sources = PriceSources::from_specs(&specs(&["akcniceny|https://www.akcniceny.cz/branik", "kupi|https://www.kupi.cz/branik",
+            "shop:Albert|https://www.albert.cz/branik", "shop:Tesco|https://nakup.itesco.cz/branik"])).unwrap();
+        let pages = [
+            Ok(include_str!("fixtures/akcniceny.html").to_string()),
+            Ok(include_str!("fixtures/kupi.html").to_string()),
+            Ok(include_str!("fixtures/product_page.html").to_string()),
+            Err(PriceError::Fetch("timeout".to_string()))
+        ];
+        let quotes = sources.quotes_in_pages(&pages, today);
+        assert_eq!(5, quotes.len());
+        assert_eq!(Some(PriceQuote {
+            price: Money::from_halere(3790),
+            store: Some("Penny".to_string()),
+            source: "kupi.cz".to_string(),
+            url: "https://www.kupi.cz/branik".to_string(),
+            valid_until: None
+        }), quotes.into_iter().min_by_key(|q| q.price));
+        // invalid pages are skipped
+        let pages = [Ok("".to_string()), Ok("<html></html>".to_string()), pages[2].clone(), Err(PriceError::Fetch("timeout".to_string()))];
+        assert_eq!(vec![Money::from_halere(4190)], sources.quotes_in_pages(&pages, today).iter().map(|q| q.price).collect::<Vec<Money>>());
+        let pages = [Ok("".to_string()), Ok("".to_string()), Ok("".to_string()), Ok("".to_string())];
+        assert!(sources.quotes_in_pages(&pages, today).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_page_timeout() {
+        // accepts the connection and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/branik", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut connections = vec![];
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+        let client = http_client(Duration::from_millis(100), Duration::from_millis(200));
+        let result = fetch_page(&client, &url).await;
+        assert!(matches!(&result, Err(PriceError::Fetch(e)) if e.starts_with("timed out")), "{:?}", result);
+        server.abort();
+    }
+
+    #[test]
+    fn test_parse_price_text() {
+        assert_eq!(Some(Money::from_halere(3990)), parse_price_text("39,90\u{a0}Kč"));
+        assert_eq!(Some(Money::from_units(39)), parse_price_text("39,-"));
+    }
+}
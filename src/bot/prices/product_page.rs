@@ -0,0 +1,106 @@
+use chrono::NaiveDate;
+
+use crate::money::Money;
+
+use super::{PriceError, PriceQuote, PriceSource, html::attribute, schema::{Offer, OfferError}};
+
+// Product page in the e-shop of a single chain, the price is in schema.org offer or in the Open Graph meta tags
+pub struct ProductPage {
+    store: String,
+    url: String
+}
+
+impl ProductPage {
+    pub fn new(store: &str, url: String) -> ProductPage {
+        ProductPage { store: store.to_string(), url }
+    }
+
+    // "www.albert.cz"
+    fn host(&self) -> &str {
+        let url = self.url.split_once("://").map(|(_, rest)| rest).unwrap_or(&self.url);
+        url.split('/').next().unwrap_or(url)
+    }
+
+    fn meta_price(&self, page: &str) -> Result<Money, PriceError> {
+        let amount = Self::meta(page, "product:price:amount")
+            .ok_or(PriceError::Page("Failed to find the 'product:price:amount' meta tag".to_string()))?;
+        match Self::meta(page, "product:price:currency") {
+            Some(currency) if currency != "CZK" => Err(OfferError::UnsupportedCurrency(currency).into()),
+            _ => Money::parse(&amount).ok_or(OfferError::InvalidPrice(amount).into())
+        }
+    }
+
+    // <meta property="product:price:amount" content="41.90">
+    fn meta(page: &str, property: &str) -> Option<String> {
+        page.match_indices("<meta")
+            .filter_map(|(i, _)| page[i..].find('>').map(|end| &page[i..=i + end]))
+            .find(|tag| attribute(tag, "property") == Some(property))
+            .and_then(|tag| attribute(tag, "content"))
+            .map(|c| c.to_string())
+    }
+}
+
+impl PriceSource for ProductPage {
+    fn name(&self) -> &str {
+        &self.store
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn parse(&self, page: &str, today: NaiveDate) -> Result<Vec<PriceQuote>, PriceError> {
+        let (price, valid_until) = match Offer::extract_czk(page) {
+            Ok(offers) => offers.into_iter()
+                .filter(|o| o.is_available(today))
+                .map(|o| (o.low_price, o.valid_through))
+                .min()
+                .ok_or(PriceError::Page("No offer in stock and valid today".to_string()))?,
+            Err(OfferError::NoOffer) => (self.meta_price(page)?, None),
+            Err(e) => return Err(e.into())
+        };
+        Ok(vec![PriceQuote { price, store: Some(self.store.clone()), source: self.host().to_string(), url: self.url.clone(), valid_until }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let source = ProductPage::new("Albert", "https://www.albert.cz/branik".to_string());
+        assert_eq!(Ok(vec![PriceQuote {
+            price: Money::from_halere(4190),
+            store: Some("Albert".to_string()),
+            source: "www.albert.cz".to_string(),
+            url: "https://www.albert.cz/branik".to_string(),
+            valid_until: None
+        }]), source.parse(include_str!("fixtures/product_page.html"), date(17)));
+        // schema.org offer is preferred
+        let quotes = source.parse(r#"<meta property="product:price:amount" content="41.90">
+            <script type="application/ld+json">{"@type": "Product", "offers": {"@type": "Offer", "price": 38.9, "priceValidUntil": "2026-10-20"}}</script>"#, date(17)).unwrap();
+        assert_eq!((Money::from_halere(3890), Some(date(20))), (quotes[0].price, quotes[0].valid_until));
+        assert!(source.parse("<html><head><title>Braník</title></head></html>", date(17)).is_err());
+        assert_eq!(Err(PriceError::Offer(OfferError::UnsupportedCurrency("EUR".to_string()))),
+            source.parse(r#"<meta property="product:price:amount" content="1.79"><meta property="product:price:currency" content="EUR">"#, date(17)));
+    }
+
+    #[test]
+    fn test_parse_current_offers() {
+        let source = ProductPage::new("Albert", "https://www.albert.cz/branik".to_string());
+        let page = include_str!("fixtures/product_page_offers.html");
+        let lowest = |today: NaiveDate| source.parse(page, today).map(|quotes| (quotes[0].price, quotes[0].valid_until));
+        // the sale ending on 16. 10. is over, the one from 19. 10. hasn't started yet and 37,90 is sold out
+        assert_eq!(Ok((Money::from_halere(3990), Some(date(21)))), lowest(date(17)));
+        assert_eq!(Ok((Money::from_halere(3590), Some(date(16)))), lowest(date(16)));
+        assert_eq!(Ok((Money::from_halere(3690), None)), lowest(date(22)));
+        // the meta tags are not used for ended offers
+        assert_eq!(Err(PriceError::Page("No offer in stock and valid today".to_string())), lowest(date(7)));
+        assert!(source.parse(r#"<script type="application/ld+json">{"@type": "Offer", "price": "38.90", "availability": "https://schema.org/OutOfStock"}</script>"#, date(17)).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use std::{future::Future, sync::{Mutex, MutexGuard, RwLock}, time::Duration};
+
+use chrono::{DateTime, Days, Local, NaiveTime, TimeZone};
+use tokio::task::JoinHandle;
+
+use crate::money::Money;
+
+use super::{PriceSources, history::{PriceHistory, PriceRecord}, sanity::PriceGuard};
+
+// Braník price shared by the bot and the refresh task. The price and where it comes from are swapped together,
+// so a reader never sees the price of one fetch with the record of another.
+pub struct BranikPrice {
+    sources: PriceSources,
+    history: Mutex<PriceHistory>,
+    guard: Mutex<PriceGuard>,
+    default_price: Money,
+    // older last known price is not used
+    max_age_days: i64,
+    current: RwLock<CurrentPrice>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentPrice {
+    pub price: Money,
+    // where and when the price was found, none when the default is used
+    pub record: Option<PriceRecord>
+}
+
+// when the price is fetched again besides the start of the bot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshSchedule {
+    // every interval after the last refresh
+    pub interval: Option<Duration>,
+    // every day at the times, i.e. right after midnight when the new leaflets start
+    pub daily_times: Vec<NaiveTime>
+}
+
+impl BranikPrice {
+    // the last known price is used until the price is updated
+    pub fn new(sources: PriceSources, history: PriceHistory, guard: PriceGuard, default_price: Money, max_age_days: i64) -> BranikPrice {
+        let record = history.last_within(Local::now().fixed_offset(), max_age_days).cloned();
+        let price = record.as_ref().map(|r| r.quote.price).unwrap_or(default_price);
+        BranikPrice {
+            sources,
+            history: Mutex::new(history),
+            guard: Mutex::new(guard),
+            default_price,
+            max_age_days,
+            current: RwLock::new(CurrentPrice { price, record })
+        }
+    }
+
+    pub fn price(&self) -> Money {
+        self.current.read().unwrap().price
+    }
+
+    pub fn current(&self) -> CurrentPrice {
+        self.current.read().unwrap().clone()
+    }
+
+    // price set by hand, without any record
+    pub fn set(&self, price: Money) {
+        self.replace(CurrentPrice { price, record: None });
+    }
+
+    pub fn history(&self) -> MutexGuard<'_, PriceHistory> {
+        self.history.lock().unwrap()
+    }
+
+    // lowest price of the sources passing the checks, the last known price or the default one when there is none
+    pub async fn update(&self) {
+        println!("Update price!");
+        let quotes = self.sources.quotes().await;
+        let now = Local::now().fixed_offset();
+        let current = {
+            let mut history = self.history();
+            let quote = self.guard.lock().unwrap().review(&quotes, &history, now);
+            match quote {
+                Some(quote) => {
+                    println!("Lowest price {}", quote);
+                    CurrentPrice { price: quote.price, record: Some(history.record(&quote, now).clone()) }
+                },
+                None => match history.last_within(now, self.max_age_days) {
+                    Some(record) => {
+                        println!("No price found, using the last known {} from {}", record.quote, record.time.format("%d.%m.%Y %H:%M"));
+                        CurrentPrice { price: record.quote.price, record: Some(record.clone()) }
+                    },
+                    None => {
+                        if let Some(record) = history.last() {
+                            println!("The last known {} from {} is too old", record.quote, record.time.format("%d.%m.%Y %H:%M"));
+                        }
+                        println!("No price found, using default {} Kč", self.default_price);
+                        CurrentPrice { price: self.default_price, record: None }
+                    }
+                }
+            }
+        };
+        self.replace(current);
+    }
+
+    fn replace(&self, current: CurrentPrice) {
+        let mut guard = self.current.write().unwrap();
+        if guard.price != current.price {
+            println!("Branik price changed from {} Kč to {} Kč", guard.price, current.price);
+        }
+        *guard = current;
+    }
+}
+
+impl RefreshSchedule {
+    pub fn is_empty(&self) -> bool {
+        self.interval.is_none() && self.daily_times.is_empty()
+    }
+
+    // the earliest of the interval after the last refresh and the next daily time, none when nothing is scheduled
+    pub fn next_after<Tz: TimeZone>(&self, last: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let after_interval = self.interval
+            .and_then(|i| chrono::Duration::from_std(i).ok())
+            .and_then(|i| last.clone().checked_add_signed(i));
+        let timezone = last.timezone();
+        let today = last.date_naive();
+        let daily = [today, today + Days::new(1)].into_iter()
+            .flat_map(|day| self.daily_times.iter().map(move |time| day.and_time(*time)))
+            // times skipped by the daylight saving change are left out
+            .filter_map(|time| timezone.from_local_datetime(&time).earliest())
+            .filter(|time| time > last)
+            .min();
+        after_interval.into_iter().chain(daily).min()
+    }
+
+    // runs the refresh until the task is aborted, nothing is spawned for an empty schedule
+    pub fn spawn<F, Fut>(self, refresh: F) -> Option<JoinHandle<()>>
+    where F: Fn() -> Fut + Send + 'static, Fut: Future<Output = ()> + Send {
+        if self.is_empty() {
+            return None;
+        }
+        Some(tokio::spawn(async move {
+            let mut last = Local::now();
+            while let Some(next) = self.next_after(&last) {
+                println!("Next price refresh at {}", next.format("%d.%m.%Y %H:%M"));
+                tokio::time::sleep((next - Local::now()).to_std().unwrap_or_default()).await;
+                refresh().await;
+                last = Local::now();
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process, sync::Arc};
+
+    use chrono::FixedOffset;
+
+    use crate::bot::prices::PriceQuote;
+
+    use super::*;
+
+    fn time(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(time).unwrap()
+    }
+
+    fn schedule(hours: Option<u64>, times: &[&str]) -> RefreshSchedule {
+        RefreshSchedule {
+            interval: hours.map(|h| Duration::from_secs(h * 60 * 60)),
+            daily_times: times.iter().map(|t| NaiveTime::parse_from_str(t, "%H:%M").unwrap()).collect()
+        }
+    }
+
+    fn quote(price: i64) -> PriceQuote {
+        PriceQuote {
+            price: Money::from_halere(price),
+            store: Some("Penny".to_string()),
+            source: "kupi.cz".to_string(),
+            url: "https://www.kupi.cz/branik".to_string(),
+            valid_until: None
+        }
+    }
+
+    fn price(name: &str) -> (BranikPrice, String) {
+        let path = env::temp_dir().join(format!("branik_refresh_{}_{}", name, process::id())).to_string_lossy().to_string();
+        let _ = fs::remove_file(&path);
+        let guard = PriceGuard::new(Money::from_units(15), Money::from_units(150), 30, PriceHistory::load(&format!("{}_quarantine", path)));
+        (BranikPrice::new(PriceSources::new(vec![]), PriceHistory::load(&path), guard, Money::from_halere(3990), 7), path)
+    }
+
+    #[test]
+    fn test_next_after() {
+        let every_six_hours = schedule(Some(6), &["00:05"]);
+        assert_eq!(Some(time("2026-10-17T16:00:00+02:00")), every_six_hours.next_after(&time("2026-10-17T10:00:00+02:00")));
+        assert_eq!(Some(time("2026-10-18T00:05:00+02:00")), every_six_hours.next_after(&time("2026-10-17T20:00:00+02:00")));
+        // the daily time is not repeated right after it
+        assert_eq!(Some(time("2026-10-18T06:05:00+02:00")), every_six_hours.next_after(&time("2026-10-18T00:05:00+02:00")));
+        let daily = schedule(None, &["12:00", "00:05"]);
+        assert_eq!(Some(time("2026-10-17T12:00:00+02:00")), daily.next_after(&time("2026-10-17T00:05:00+02:00")));
+        assert_eq!(Some(time("2026-10-18T00:05:00+02:00")), daily.next_after(&time("2026-10-17T12:00:00+02:00")));
+        assert!(schedule(None, &[]).is_empty());
+        assert_eq!(None, schedule(None, &[]).next_after(&time("2026-10-17T12:00:00+02:00")));
+    }
+
+    #[tokio::test]
+    async fn test_update() {
+        let (price, path) = price("update");
+        price.update().await;
+        assert_eq!(CurrentPrice { price: Money::from_halere(3990), record: None }, price.current());
+        // too old to be used
+        price.history().record(&quote(2990), Local::now().fixed_offset() - Days::new(8));
+        price.update().await;
+        assert_eq!(CurrentPrice { price: Money::from_halere(3990), record: None }, price.current());
+        price.history().record(&quote(3490), Local::now().fixed_offset());
+        price.update().await;
+        let _ = fs::remove_file(&path);
+        let current = price.current();
+        assert_eq!(Money::from_halere(3490), current.price);
+        assert_eq!(Some(quote(3490)), current.record.map(|r| r.quote));
+        price.set(Money::from_halere(4290));
+        assert_eq!(CurrentPrice { price: Money::from_halere(4290), record: None }, price.current());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spawn() {
+        let (price, path) = price("spawn");
+        let price = Arc::new(price);
+        let refresh = |price: Arc<BranikPrice>| move || {
+            let price = price.clone();
+            async move { price.update().await }
+        };
+        assert!(schedule(None, &[]).spawn(refresh(price.clone())).is_none());
+        let task = RefreshSchedule { interval: Some(Duration::from_millis(50)), daily_times: vec![] }.spawn(refresh(price.clone())).unwrap();
+        price.history().record(&quote(3490), Local::now().fixed_offset());
+        // waits for the refresh, a slow machine gets a few seconds
+        for _ in 0..500 {
+            if price.price() == Money::from_halere(3490) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        task.abort();
+        let _ = fs::remove_file(&path);
+        assert_eq!(Money::from_halere(3490), price.price());
+    }
+}
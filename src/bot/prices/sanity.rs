@@ -0,0 +1,199 @@
+use std::fmt;
+
+use chrono::{DateTime, Days, FixedOffset};
+
+use crate::money::Money;
+
+use super::{PriceQuote, history::PriceHistory};
+
+// Checks of the scraped prices before they are quoted - a multipack, another product or a parsing glitch
+// (3,99 or 399 instead of 39,90) must not get to the replies. Prices outside of the bounds are rejected,
+// big jumps from the recent prices are quarantined until another source gives the same price or the same
+// source gives it again in the next fetch.
+pub struct PriceGuard {
+    pub min_price: Money,
+    pub max_price: Money,
+    // change from the median of the recent prices still accepted without confirmation
+    pub max_change_percent: i64,
+    // the latest suspicious quote of every store of a page, a quote confirms the earlier one of the same page
+    quarantine: PriceHistory
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Accepted,
+    // suspicious, but confirmed
+    Confirmed(String),
+    Quarantined(String),
+    Rejected(String)
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::Accepted => write!(f, "accepted"),
+            Decision::Confirmed(reason) => write!(f, "confirmed, {}", reason),
+            Decision::Quarantined(reason) => write!(f, "quarantined, {}", reason),
+            Decision::Rejected(reason) => write!(f, "rejected, {}", reason)
+        }
+    }
+}
+
+impl PriceGuard {
+    // days of the history the price is compared to
+    const RECENT_DAYS: u64 = 14;
+    // quarantined quote older than that doesn't confirm anymore
+    const QUARANTINE_DAYS: u64 = 2;
+    // prices confirming each other may differ a bit, i.e. rounding of the different sources
+    const CONFIRM_TOLERANCE_PERCENT: i64 = 5;
+
+    pub fn new(min_price: Money, max_price: Money, max_change_percent: i64, quarantine: PriceHistory) -> PriceGuard {
+        PriceGuard { min_price, max_price, max_change_percent, quarantine }
+    }
+
+    // the lowest quote passing the checks, every decision is logged and the quarantined quotes are stored
+    pub fn review(&mut self, quotes: &[PriceQuote], history: &PriceHistory, now: DateTime<FixedOffset>) -> Option<PriceQuote> {
+        self.quarantine.retain(|r| r.time >= now - Days::new(Self::QUARANTINE_DAYS));
+        let mut sorted: Vec<&PriceQuote> = quotes.iter().collect();
+        sorted.sort_by_key(|q| q.price);
+        for quote in sorted {
+            let decision = self.decide(quote, quotes, history, now);
+            println!("Price check of {}: {}", quote, decision);
+            match decision {
+                Decision::Accepted => return Some(quote.clone()),
+                Decision::Confirmed(_) => {
+                    self.quarantine.retain(|r| r.quote.url != quote.url);
+                    return Some(quote.clone());
+                },
+                Decision::Quarantined(_) => {
+                    self.quarantine.retain(|r| r.quote.url != quote.url || r.quote.store != quote.store);
+                    self.quarantine.record(quote, now);
+                },
+                Decision::Rejected(_) => ()
+            }
+        }
+        None
+    }
+
+    // quotes are all the quotes of the fetch, other sources among them can confirm the quote
+    pub fn decide(&self, quote: &PriceQuote, quotes: &[PriceQuote], history: &PriceHistory, now: DateTime<FixedOffset>) -> Decision {
+        if quote.price < self.min_price || quote.price > self.max_price {
+            return Decision::Rejected(format!("outside of {} - {} Kč", self.min_price, self.max_price));
+        }
+        let today = now.date_naive();
+        let mut recent: Vec<Money> = history.between(today - Days::new(Self::RECENT_DAYS), today).iter().map(|r| r.quote.price).collect();
+        if recent.is_empty() {
+            return Decision::Accepted;
+        }
+        recent.sort();
+        let reference = recent[recent.len() / 2];
+        let change = Self::change_percent(reference, quote.price);
+        if change.abs() <= self.max_change_percent {
+            return Decision::Accepted;
+        }
+        let reason = format!("{} % {} the recent {} Kč", change.abs(), if change < 0 { "below" } else { "above" }, reference);
+        if let Some(other) = quotes.iter().find(|q| q.url != quote.url && Self::is_same_price(q.price, quote.price)) {
+            return Decision::Confirmed(format!("{}, {} Kč from {} as well", reason, other.price, other.source));
+        }
+        let confirming = self.quarantine.between(today - Days::new(Self::QUARANTINE_DAYS), today).into_iter()
+            .find(|r| r.time < now && r.quote.url == quote.url && Self::is_same_price(r.quote.price, quote.price));
+        match confirming {
+            Some(record) => Decision::Confirmed(format!("{}, the same in the fetch at {}", reason, record.time.format("%d.%m.%Y %H:%M"))),
+            None => Decision::Quarantined(reason)
+        }
+    }
+
+    // -50 when the price is half of the reference
+    fn change_percent(reference: Money, price: Money) -> i64 {
+        (price.halere() - reference.halere()) * 100 / reference.halere().max(1)
+    }
+
+    fn is_same_price(a: Money, b: Money) -> bool {
+        Self::change_percent(a, b).abs() <= Self::CONFIRM_TOLERANCE_PERCENT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    fn time(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(time).unwrap()
+    }
+
+    fn quote(price: i64, url: &str) -> PriceQuote {
+        PriceQuote {
+            price: Money::from_halere(price),
+            store: None,
+            source: url.split('.').nth(1).unwrap_or(url).to_string(),
+            url: url.to_string(),
+            valid_until: None
+        }
+    }
+
+    fn guard(name: &str) -> PriceGuard {
+        let path = env::temp_dir().join(format!("branik_quarantine_{}_{}", name, process::id())).to_string_lossy().to_string();
+        let _ = fs::remove_file(&path);
+        PriceGuard::new(Money::from_units(15), Money::from_units(150), 30, PriceHistory::load(&path))
+    }
+
+    // 39,90 Kč for the last days
+    fn history() -> PriceHistory {
+        PriceHistory::read_history("", "2026-10-10T08:00:00+02:00|39.90||akcniceny.cz|https://www.akcniceny.cz/branik|
+2026-10-14T08:00:00+02:00|41.90||akcniceny.cz|https://www.akcniceny.cz/branik|
+2026-10-16T08:00:00+02:00|39.90||akcniceny.cz|https://www.akcniceny.cz/branik|")
+    }
+
+    #[test]
+    fn test_decide() {
+        let guard = guard("decide");
+        let now = time("2026-10-17T08:00:00+02:00");
+        let check = |price: i64, history: &PriceHistory| guard.decide(&quote(price, "https://www.akcniceny.cz/branik"), &[], history, now);
+        assert_eq!(Decision::Accepted, check(3490, &history()));
+        assert_eq!(Decision::Accepted, check(4990, &history()));
+        assert_eq!(Decision::Rejected("outside of 15,00 - 150,00 Kč".to_string()), check(399, &history()));
+        assert_eq!(Decision::Rejected("outside of 15,00 - 150,00 Kč".to_string()), check(39900, &history()));
+        assert_eq!(Decision::Quarantined("50 % below the recent 39,90 Kč".to_string()), check(1990, &history()));
+        assert_eq!(Decision::Quarantined("150 % above the recent 39,90 Kč".to_string()), check(9990, &history()));
+        // nothing to compare to
+        assert_eq!(Decision::Accepted, check(1990, &PriceHistory::read_history("", "")));
+        let old = PriceHistory::read_history("", "2026-09-01T08:00:00+02:00|39.90||akcniceny.cz|https://www.akcniceny.cz/branik|");
+        assert_eq!(Decision::Accepted, check(1990, &old));
+    }
+
+    #[test]
+    fn test_confirmed_by_another_source() {
+        let now = time("2026-10-17T08:00:00+02:00");
+        let quotes = [quote(1990, "https://www.akcniceny.cz/branik"), quote(1990, "https://www.akcniceny.cz/branik"), quote(2090, "https://www.kupi.cz/branik")];
+        assert_eq!(Decision::Confirmed("50 % below the recent 39,90 Kč, 20,90 Kč from kupi as well".to_string()),
+            guard("source").decide(&quotes[0], &quotes, &history(), now));
+        // a store of the same page doesn't confirm
+        assert!(matches!(guard("source").decide(&quotes[0], &quotes[..2], &history(), now), Decision::Quarantined(_)));
+    }
+
+    #[test]
+    fn test_review() {
+        let mut guard = guard("review");
+        let history = history();
+        let glitch = quote(399, "https://www.kupi.cz/branik");
+        let sale = quote(1990, "https://www.akcniceny.cz/branik");
+        let usual = quote(3990, "https://www.albert.cz/branik");
+        // the lowest acceptable one wins, the sale waits in quarantine
+        let quotes = [usual.clone(), sale.clone(), glitch.clone()];
+        assert_eq!(Some(usual), guard.review(&quotes, &history, time("2026-10-17T08:00:00+02:00")));
+        assert_eq!(Some(&sale), guard.quarantine.last().map(|r| &r.quote));
+        assert_eq!(None, guard.review(std::slice::from_ref(&sale), &history, time("2026-10-17T08:00:00+02:00")));
+        // only the latest quote of the page waits
+        let today = time("2026-10-17T08:00:00+02:00").date_naive();
+        assert_eq!(1, guard.quarantine.between(today, today).len());
+        // until the next fetch gives the same price
+        assert_eq!(Some(sale.clone()), guard.review(&[sale.clone(), glitch], &history, time("2026-10-17T14:00:00+02:00")));
+        assert_eq!(None, guard.quarantine.last());
+        // the quarantine gets old
+        assert_eq!(None, guard.review(std::slice::from_ref(&sale), &history, time("2026-10-17T20:00:00+02:00")));
+        assert_eq!(None, guard.review(&[sale], &history, time("2026-10-21T14:00:00+02:00")));
+        assert_eq!(Some(time("2026-10-21T14:00:00+02:00")), guard.quarantine.last().map(|r| r.time));
+    }
+}
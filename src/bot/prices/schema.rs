@@ -0,0 +1,285 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde_json::Value;
+
+use crate::money::Money;
+
+use super::html::{attribute, element_end, element_text, has_flag, tags};
+
+// schema.org Offer or AggregateOffer of a product page, read from JSON-LD blocks or microdata attributes.
+// A single Offer has the same low and high price.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Offer {
+    pub low_price: Money,
+    pub high_price: Money,
+    // "CZK"
+    pub currency: Option<String>,
+    // "InStock", "OutOfStock" without the schema.org url
+    pub availability: Option<String>,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_through: Option<NaiveDate>,
+    pub seller: Option<String>
+}
+
+// step of the extraction that failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OfferError {
+    // neither JSON-LD nor microdata with an offer on the page
+    NoOffer,
+    InvalidJsonLd(String),
+    // offer without "price" nor "lowPrice"
+    MissingPrice,
+    InvalidPrice(String),
+    InvalidDate(String),
+    // offers found only in other currencies
+    UnsupportedCurrency(String)
+}
+
+impl fmt::Display for OfferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OfferError::NoOffer => write!(f, "no schema.org Offer or AggregateOffer in JSON-LD nor microdata"),
+            OfferError::InvalidJsonLd(e) => write!(f, "invalid JSON-LD block: {}", e),
+            OfferError::MissingPrice => write!(f, "offer without price or lowPrice"),
+            OfferError::InvalidPrice(price) => write!(f, "invalid price '{}'", price),
+            OfferError::InvalidDate(date) => write!(f, "invalid date '{}'", date),
+            OfferError::UnsupportedCurrency(currency) => write!(f, "offers only in {}, not CZK", currency)
+        }
+    }
+}
+
+impl Offer {
+    const TYPES: [&'static str; 2] = ["Offer", "AggregateOffer"];
+
+    // offers of JSON-LD take precedence, microdata is read only when there are none.
+    // The error of the first invalid offer is returned when no offer can be read.
+    pub fn extract(page: &str) -> Result<Vec<Offer>, OfferError> {
+        let mut results = Self::json_ld_offers(page);
+        if !results.iter().any(|r| r.is_ok()) {
+            results.extend(Self::microdata_offers(page));
+        }
+        let mut error = None;
+        let mut offers = vec![];
+        for result in results {
+            match result {
+                Ok(offer) => offers.push(offer),
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        match offers.is_empty() {
+            true => Err(error.unwrap_or(OfferError::NoOffer)),
+            false => Ok(offers)
+        }
+    }
+
+    // in stock and valid on the day, missing dates don't limit the offer
+    pub fn is_available(&self, today: NaiveDate) -> bool {
+        self.availability.as_deref() != Some("OutOfStock")
+            && self.valid_from.is_none_or(|from| from <= today)
+            && self.valid_through.is_none_or(|through| through >= today)
+    }
+
+    // offers in CZK, the currency is expected to be CZK when missing
+    pub fn extract_czk(page: &str) -> Result<Vec<Offer>, OfferError> {
+        let offers = Self::extract(page)?;
+        let currency = offers[0].currency.clone().unwrap_or_default();
+        let czk: Vec<Offer> = offers.into_iter().filter(|o| o.currency.as_deref().unwrap_or("CZK") == "CZK").collect();
+        match czk.is_empty() {
+            true => Err(OfferError::UnsupportedCurrency(currency)),
+            false => Ok(czk)
+        }
+    }
+
+    // property values are looked up by the schema.org names
+    fn from_properties(property: impl Fn(&str) -> Option<String>) -> Result<Offer, OfferError> {
+        let price = |value: String| Money::parse(&value).ok_or(OfferError::InvalidPrice(value));
+        let date = |value: Option<String>| match value {
+            None => Ok(None),
+            // date time "2026-10-21T00:00:00+02:00" is cut to the date
+            Some(value) => NaiveDate::parse_from_str(value.get(..10).unwrap_or(&value), "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_| OfferError::InvalidDate(value))
+        };
+        let low_price = price(property("lowPrice").or(property("price")).ok_or(OfferError::MissingPrice)?)?;
+        let high_price = match property("highPrice") {
+            Some(high) => price(high)?,
+            None => low_price
+        };
+        Ok(Offer {
+            low_price,
+            high_price,
+            currency: property("priceCurrency"),
+            availability: property("availability")
+                .map(|a| a.rsplit('/').next().unwrap_or(&a).to_string()),
+            valid_from: date(property("validFrom"))?,
+            valid_through: date(property("priceValidUntil").or(property("validThrough")))?,
+            seller: property("seller")
+        })
+    }
+
+    // <script type="application/ld+json">{"@type": "Product", "offers": {"@type": "Offer", "price": "39.90"}}</script>
+    fn json_ld_offers(page: &str) -> Vec<Result<Offer, OfferError>> {
+        let mut offers = vec![];
+        for (start, tag) in tags(page, 0, page.len()) {
+            if !tag.starts_with("<script") || attribute(tag, "type") != Some("application/ld+json") {
+                continue;
+            }
+            let end = element_end(page, start);
+            let json = &page[start + tag.len()..page[..end].rfind("</").unwrap_or(end).max(start + tag.len())];
+            match serde_json::from_str::<Value>(json) {
+                Ok(value) => Self::collect_json_offers(&value, &mut offers),
+                Err(e) => offers.push(Err(OfferError::InvalidJsonLd(e.to_string())))
+            }
+        }
+        offers
+    }
+
+    // offers nested anywhere - in "@graph", "offers" of a product or of an aggregate offer
+    fn collect_json_offers(value: &Value, offers: &mut Vec<Result<Offer, OfferError>>) {
+        match value {
+            Value::Array(values) => values.iter().for_each(|v| Self::collect_json_offers(v, offers)),
+            Value::Object(object) => {
+                let is_offer = match object.get("@type") {
+                    Some(Value::String(t)) => Self::TYPES.contains(&t.as_str()),
+                    Some(Value::Array(types)) => types.iter().any(|t| t.as_str().is_some_and(|t| Self::TYPES.contains(&t))),
+                    _ => false
+                };
+                if is_offer {
+                    offers.push(Self::from_properties(|name| match object.get(name)? {
+                        Value::String(s) => Some(s.trim().to_string()),
+                        Value::Number(n) => Some(n.to_string()),
+                        // seller is an organization
+                        Value::Object(o) => o.get("name")?.as_str().map(|s| s.trim().to_string()),
+                        _ => None
+                    }));
+                }
+                object.values().for_each(|v| Self::collect_json_offers(v, offers));
+            },
+            _ => ()
+        }
+    }
+
+    // <div itemscope itemtype="https://schema.org/Offer"><meta itemprop="price" content="39.90"></div>
+    fn microdata_offers(page: &str) -> Vec<Result<Offer, OfferError>> {
+        tags(page, 0, page.len()).into_iter()
+            .filter(|(_, tag)| has_flag(tag, "itemscope")
+                && attribute(tag, "itemtype").is_some_and(|t| Self::TYPES.contains(&t.rsplit('/').next().unwrap_or(t))))
+            .map(|(start, tag)| {
+                let properties = Self::scope_properties(page, start + tag.len(), element_end(page, start));
+                Self::from_properties(|name| properties.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone()))
+            })
+            .collect()
+    }
+
+    // "itemprop" values of the scope, properties of the nested scopes are left out except their name
+    fn scope_properties(page: &str, from: usize, to: usize) -> Vec<(String, String)> {
+        let mut properties = vec![];
+        let mut pos = from;
+        for (start, tag) in tags(page, from, to) {
+            if start < pos {
+                continue;
+            }
+            pos = start + tag.len();
+            let nested = has_flag(tag, "itemscope");
+            if nested {
+                pos = element_end(page, start);
+            }
+            let Some(name) = attribute(tag, "itemprop") else {
+                continue;
+            };
+            let value = match nested {
+                true => Self::scope_properties(page, start + tag.len(), pos).into_iter()
+                    .find(|(n, _)| n == "name").map(|(_, v)| v)
+                    .or(element_text(page, start)),
+                false => attribute(tag, "content").or(attribute(tag, "datetime")).or(attribute(tag, "href"))
+                    .map(|v| v.trim().to_string())
+                    .or(element_text(page, start))
+            };
+            if let Some(value) = value {
+                properties.push((name.to_string(), value));
+            }
+        }
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+    }
+
+    #[test]
+    fn test_json_ld() {
+        let offers = Offer::extract(include_str!("fixtures/akcniceny.html")).unwrap();
+        assert_eq!(4, offers.len());
+        assert_eq!(Offer {
+            low_price: Money::from_halere(3990),
+            high_price: Money::from_halere(4990),
+            currency: Some("CZK".to_string()),
+            availability: Some("InStock".to_string()),
+            valid_from: None,
+            valid_through: None,
+            seller: None
+        }, offers[0]);
+        assert_eq!(Offer {
+            low_price: Money::from_halere(3990),
+            high_price: Money::from_halere(3990),
+            currency: Some("CZK".to_string()),
+            availability: Some("InStock".to_string()),
+            valid_from: date("2026-10-15"),
+            valid_through: date("2026-10-21"),
+            seller: Some("Albert".to_string())
+        }, offers[1]);
+        // number price and date time
+        let page = r#"<script type="application/ld+json">
+            {"@graph": [{"@type": "WebPage"}, {"@type": ["Offer"], "price": 42.9, "priceValidUntil": "2026-10-22T23:59:59+02:00"}]}
+        </script>"#;
+        let offers = Offer::extract(page).unwrap();
+        assert_eq!((Money::from_halere(4290), date("2026-10-22")), (offers[0].low_price, offers[0].valid_through));
+    }
+
+    #[test]
+    fn test_microdata() {
+        let offers = Offer::extract(include_str!("fixtures/akcniceny_microdata.html")).unwrap();
+        let summary: Vec<(Money, Money, Option<&str>, Option<&str>)> = offers.iter()
+            .map(|o| (o.low_price, o.high_price, o.seller.as_deref(), o.availability.as_deref())).collect();
+        assert_eq!(vec![
+            (Money::from_halere(3990), Money::from_halere(4990), None, None),
+            (Money::from_halere(3990), Money::from_halere(3990), Some("Albert"), Some("InStock")),
+            (Money::from_halere(4990), Money::from_halere(4990), Some("Tesco"), Some("OutOfStock"))
+        ], summary);
+        assert_eq!(Some("CZK".to_string()), offers[0].currency);
+        assert_eq!((date("2026-10-15"), date("2026-10-21")), (offers[1].valid_from, offers[1].valid_through));
+    }
+
+    #[test]
+    fn test_is_available() {
+        let offers = Offer::extract(include_str!("fixtures/akcniceny_microdata.html")).unwrap();
+        let available = |today: &str| offers.iter().map(|o| o.is_available(date(today).unwrap())).collect::<Vec<bool>>();
+        // Tesco is sold out, Albert sells from 15. 10. to 21. 10.
+        assert_eq!(vec![true, false, false], available("2026-10-14"));
+        assert_eq!(vec![true, true, false], available("2026-10-15"));
+        assert_eq!(vec![true, true, false], available("2026-10-21"));
+        assert_eq!(vec![true, false, false], available("2026-10-22"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Err(OfferError::NoOffer), Offer::extract("<html><body>Nic</body></html>"));
+        assert!(matches!(Offer::extract(r#"<script type="application/ld+json">{"@type": "Offer",</script>"#), Err(OfferError::InvalidJsonLd(_))));
+        assert_eq!(Err(OfferError::MissingPrice), Offer::extract(r#"<div itemscope itemtype="https://schema.org/Offer"><span itemprop="name">Braník</span></div>"#));
+        assert_eq!(Err(OfferError::InvalidPrice("zdarma".to_string())),
+            Offer::extract(r#"<div itemscope itemtype="http://schema.org/Offer"><meta itemprop="price" content="zdarma"></div>"#));
+        assert_eq!(Err(OfferError::InvalidDate("brzy".to_string())),
+            Offer::extract(r#"<script type="application/ld+json">{"@type": "Offer", "price": "39.90", "validFrom": "brzy"}</script>"#));
+        assert_eq!(Err(OfferError::UnsupportedCurrency("EUR".to_string())),
+            Offer::extract_czk(r#"<script type="application/ld+json">{"@type": "Offer", "price": "1.79", "priceCurrency": "EUR"}</script>"#));
+        assert_eq!("invalid price 'zdarma'", OfferError::InvalidPrice("zdarma".to_string()).to_string());
+    }
+}
@@ -0,0 +1,137 @@
+use std::{collections::HashMap, fs};
+
+use super::built_in_text;
+
+// Reply texts read from "<subreddit>/<language>.txt" or "<language>.txt" template file with a template per line, i.e.
+//
+// amount=To by stacilo na {amount} Branika ve sleve!
+//
+// every template has to be present and reference only known placeholders, otherwise built-in templates
+// for the language are used
+pub struct Templates {
+    templates: HashMap<String, String>
+}
+
+impl Templates {
+    const KEYS: [&'static str; 14] = ["quote", "amount", "goods", "nothing", "nothing_goods", "foreign", "daily", "daily_less",
+        "quantity", "quantity_too_big", "keyword", "footer", "and", "to"];
+    const PLACEHOLDERS: [&'static str; 5] = ["amount", "unit", "price", "quote", "date"];
+    const BUILT_IN: [(&'static str, &'static str); 3] = [
+        ("cs", include_str!("../../templates/cs.txt")),
+        ("sk", include_str!("../../templates/sk.txt")),
+        ("en", include_str!("../../templates/en.txt"))
+    ];
+
+    // subreddit specific templates take precedence over the shared ones
+    pub fn load(dir: &str, subreddit: &str, language: &str) -> Templates {
+        let dir = dir.trim_end_matches('/');
+        let paths = [format!("{}/{}/{}.txt", dir, subreddit.to_lowercase(), language), format!("{}/{}.txt", dir, language)];
+        let Some((path, text)) = paths.iter().find_map(|p| fs::read_to_string(p).ok().map(|text| (p, text))) else {
+            println!("Failed to load templates from {}, using built-in ones", paths[1]);
+            return Self::built_in(language);
+        };
+        match Self::read_templates(&text) {
+            Ok(templates) => templates,
+            Err(errors) => {
+                println!("Invalid templates in {}, using built-in ones:\n{}", path, errors.join("\n"));
+                Self::built_in(language)
+            }
+        }
+    }
+
+    pub fn built_in(language: &str) -> Templates {
+        let text = built_in_text(&Self::BUILT_IN, language, "templates");
+        Self::read_templates(text).expect("Expected built-in templates to be valid")
+    }
+
+    // returns all problems found in the templates
+    pub fn read_templates(templates_str: &str) -> Result<Templates, Vec<String>> {
+        let mut templates = HashMap::new();
+        let mut errors = vec![];
+        for line in templates_str.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#')) {
+            let Some((key, template)) = line.split_once('=') else {
+                errors.push(format!("Invalid template line '{}'", line));
+                continue;
+            };
+            let key = key.trim();
+            if !Self::KEYS.contains(&key) {
+                errors.push(format!("Unknown template '{}'", key));
+                continue;
+            }
+            for placeholder in Self::find_placeholders(template) {
+                if !Self::PLACEHOLDERS.contains(&placeholder) {
+                    errors.push(format!("Unknown placeholder '{{{}}}' in template '{}'", placeholder, key));
+                }
+            }
+            templates.insert(key.to_string(), template.trim().to_string());
+        }
+        for key in Self::KEYS {
+            if !templates.contains_key(key) {
+                errors.push(format!("Missing template '{}'", key));
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(Templates { templates }),
+            false => Err(errors)
+        }
+    }
+
+    pub fn get(&self, key: &str) -> &str {
+        &self.templates[key]
+    }
+
+    // template with placeholders replaced by given values - [("amount", "3 palety")]
+    pub fn render(&self, key: &str, values: &[(&str, &str)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (placeholder, value) in values {
+            text = text.replace(&format!("{{{}}}", placeholder), value);
+        }
+        text
+    }
+
+    fn find_placeholders(template: &str) -> Vec<&str> {
+        let mut placeholders = vec![];
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            placeholders.push(&rest[start + 1..start + end]);
+            rest = &rest[start + end + 1..];
+        }
+        placeholders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in_templates() {
+        for (language, text) in Templates::BUILT_IN {
+            assert!(Templates::read_templates(text).is_ok(), "invalid built-in templates for {}", language);
+        }
+        let templates = Templates::built_in("en");
+        assert_eq!("That would buy 3 packs of discounted Branik!", templates.render("amount", &[("amount", "3 packs")]));
+        // unknown language falls back to czech
+        let templates = Templates::built_in("de");
+        assert_eq!("> citace", templates.render("quote", &[("quote", "citace")]));
+        assert_eq!("až", templates.get("to"));
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        let text = Templates::BUILT_IN[0].1
+            .replace("amount=To by stacilo na {amount}", "amount=To by stacilo na {amout}")
+            .replace("footer=", "neznamy=")
+            .replace("\nand=a", "\nspatny radek");
+        let errors = Templates::read_templates(&text).err().unwrap();
+        assert_eq!(vec![
+            "Unknown placeholder '{amout}' in template 'amount'".to_string(),
+            "Unknown template 'neznamy'".to_string(),
+            "Invalid template line 'spatny radek'".to_string(),
+            "Missing template 'footer'".to_string(),
+            "Missing template 'and'".to_string()], errors);
+    }
+}
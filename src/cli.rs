@@ -0,0 +1,121 @@
+use chrono::NaiveDate;
+
+use crate::config::Config;
+
+// Command line of the bot, i.e.
+//
+// branik-bot --config ./test.conf run --dry-run
+// branik-bot parse "To stalo 500 kc"
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cli {
+    pub config_path: String,
+    pub command: Command
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    // respawn loop reading and replying to the subreddit, dry run never posts
+    Run { dry_run: bool },
+    // prints parse results and the reply for the text
+    Parse(String),
+    // prints the scraped prices
+    Price,
+    // prints the recorded prices of the days, the last month by default
+    History { from: Option<NaiveDate>, to: Option<NaiveDate> },
+    // feeds comments exported to the file through the pipeline, the replies are written to the output
+    Replay { input: String, output: String }
+}
+
+impl Cli {
+    pub const USAGE: &'static str = "Usage: branik-bot [--config <path>] [command]
+
+Commands:
+    run [--dry-run]    read the subreddit and reply, default command, --dry-run never posts
+    parse <text>       print what the parser finds in the text and the reply to it
+    price              print the current Braník and goods prices
+    history [<from> [<to>]]
+                       print the Braník prices recorded from to the day (YYYY-MM-DD),
+                       the last 30 days by default
+    replay <file> [--output <path>]
+                       write replies to the comments exported to the JSON lines file,
+                       to <file>.replies by default";
+
+    // args without the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Cli, String> {
+        let mut config_path = Config::PATH.to_string();
+        let mut positional = vec![];
+        let mut dry_run = false;
+        let mut output = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" | "-c" => config_path = args.next().ok_or("Missing path after --config")?,
+                "--dry-run" => dry_run = true,
+                "--output" | "-o" => output = Some(args.next().ok_or("Missing path after --output")?),
+                _ => positional.push(arg)
+            }
+        }
+        let command = match positional.iter().map(|a| a.as_str()).collect::<Vec<&str>>()[..] {
+            [] | ["run"] => Command::Run { dry_run },
+            ["parse", text] => Command::Parse(text.to_string()),
+            ["parse", ..] => return Err("Expected single text to parse, quote it".to_string()),
+            ["price"] => Command::Price,
+            ["history", ref dates @ ..] if dates.len() <= 2 => {
+                let date = |index: usize| dates.get(index)
+                    .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", d)))
+                    .transpose();
+                Command::History { from: date(0)?, to: date(1)? }
+            },
+            ["replay", input] => Command::Replay {
+                input: input.to_string(),
+                output: output.take().unwrap_or(format!("{}.replies", input))
+            },
+            ["replay"] => return Err("Missing file to replay".to_string()),
+            _ => return Err(format!("Unknown command '{}'", positional.join(" ")))
+        };
+        if dry_run && !matches!(command, Command::Run { .. }) {
+            return Err("--dry-run is supported only by run".to_string());
+        }
+        if output.is_some() {
+            return Err("--output is supported only by replay".to_string());
+        }
+        Ok(Cli { config_path, command })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        Cli::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(Cli { config_path: Config::PATH.to_string(), command: Command::Run { dry_run: false } }), parse(&[]));
+        assert_eq!(Ok(Cli { config_path: "./test.conf".to_string(), command: Command::Run { dry_run: true } }),
+            parse(&["--config", "./test.conf", "run", "--dry-run"]));
+        assert_eq!(Command::Parse("To stalo 500 kc".to_string()), parse(&["parse", "To stalo 500 kc"]).unwrap().command);
+        assert_eq!(Command::Price, parse(&["price", "-c", "./test.conf"]).unwrap().command);
+        assert_eq!(Command::History { from: None, to: None }, parse(&["history"]).unwrap().command);
+        assert_eq!(Command::History { from: NaiveDate::from_ymd_opt(2026, 10, 1), to: NaiveDate::from_ymd_opt(2026, 10, 17) },
+            parse(&["history", "2026-10-01", "2026-10-17"]).unwrap().command);
+        assert_eq!(Command::Replay { input: "comments.jsonl".to_string(), output: "comments.jsonl.replies".to_string() },
+            parse(&["replay", "comments.jsonl"]).unwrap().command);
+        assert_eq!(Command::Replay { input: "comments.jsonl".to_string(), output: "out.jsonl".to_string() },
+            parse(&["replay", "comments.jsonl", "--output", "out.jsonl"]).unwrap().command);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["parse", "500", "kc"]).is_err());
+        assert!(parse(&["replay"]).is_err());
+        assert!(parse(&["history", "17.10.2026"]).is_err());
+        assert!(parse(&["history", "2026-10-01", "2026-10-17", "2026-10-18"]).is_err());
+        assert!(parse(&["price", "--dry-run"]).is_err());
+        assert!(parse(&["run", "--output", "out.jsonl"]).is_err());
+        assert!(parse(&["stop"]).is_err());
+    }
+}
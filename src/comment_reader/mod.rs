@@ -1,14 +1,23 @@
 use std::{fs, io::Write};
-use roux::{Subreddit, comment::CommentData};
+
+use crate::reddit::{Comment, RedditApi};
 
 pub struct CommentReader {
-    pub subreddit: Subreddit,
+    pub subreddit: String,
     pub last_comment_storage_path: String
 }
 
 impl CommentReader {
-    pub async fn read_latest_comments(&self) -> Option<Vec<CommentData>> {
-        let latest_comments = self.subreddit.latest_comments(None, Some(25)).await.expect("Expected comments");
+    const LIMIT: u32 = 25;
+
+    pub async fn read_latest_comments(&self, reddit: &impl RedditApi) -> Option<Vec<Comment>> {
+        let latest_comments = match reddit.latest_comments(&self.subreddit, Self::LIMIT).await {
+            Ok(comments) => comments,
+            Err(e) => {
+                println!("{}", e);
+                return None;
+            }
+        };
         // load last read comment id from file in order to not read it again
         let last_read_comment_id = match self.load_last_read_comment() {
             Some(comment_id) => comment_id,
@@ -17,20 +26,19 @@ impl CommentReader {
                 "".to_string()
             }
         }; 
-        let mut result: Vec<CommentData> =  vec![];
-        for comment in latest_comments.data.children {
-            if comment.data.id.clone().unwrap() == last_read_comment_id {
+        let mut result: Vec<Comment> =  vec![];
+        for comment in latest_comments {
+            if comment.id == last_read_comment_id {
                 break;
             }
-            result.push(comment.data);
+            result.push(comment);
         }
-        if result.len() == 0 {
+        if result.is_empty() {
             return None;
         }
         // save id of first comment we received
-        let latest_comment_id = &result[0].id.clone().unwrap();
-        self.save_latest_read_comment(&latest_comment_id);
-        return Some(result);
+        self.save_latest_read_comment(&result[0].id);
+        Some(result)
     }
 
     fn load_last_read_comment(&self) -> Option<String> {
@@ -43,11 +51,8 @@ impl CommentReader {
             .truncate(true)
             .create(true)
             .open(&self.last_comment_storage_path);
-        match open_file {
-            Ok(mut file) => {
-                let _ = file.write_all(comment_id.as_bytes());
-            }
-            Err(_) => ()
+        if let Ok(mut file) = open_file {
+            let _ = file.write_all(comment_id.as_bytes());
         }
     }
 }
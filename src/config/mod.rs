@@ -1,5 +1,19 @@
-use std::fs;
+use std::{collections::HashMap, env, fmt, fs, str::FromStr};
 
+use chrono::NaiveTime;
+
+use crate::{bot::prices::{PriceSources, akcniceny::AkcniCeny}, money::Money};
+
+// Bot configuration read from "branik.conf" with a setting per line, i.e.
+//
+// # reddit credentials
+// user_agent = branik-bot/0.1
+// subreddit = czech
+// goods = rohlik, kebab
+//
+// only reddit credentials and the subreddit are required, secrets can be given by BRANIK_CLIENT_ID,
+// BRANIK_CLIENT_SECRET and BRANIK_PASSWORD environment variables instead. Positional ".config" of the older
+// versions is migrated to the new file by migrate, called at the start of main.
 pub struct Config {
     pub user_agent: String,
     pub client_id: String,
@@ -7,48 +21,500 @@ pub struct Config {
     pub user_name: String,
     pub password: String,
     pub subreddit: String,
+    // base urls of the reddit api used by the http client, only changed for tests
+    pub reddit_url: String,
+    pub reddit_oauth_url: String,
+    // "roux" uses the roux crate against reddit.com, "http" talks to the configured urls
+    pub reddit_client: String,
+    // data files without own setting are stored here - last read comment, saved responses
+    pub data_dir: String,
     pub comments_per_post_limit: usize,
-    pub default_price: f32,
+    // pause between reading the new comments
+    pub poll_interval_seconds: u64,
+    // the bot is restarted after the time
+    pub run_duration_minutes: i64,
+    pub default_price: Money,
+    // pages the Braník price is scraped from - "akcniceny|<url>", "kupi|<url>" or "shop:<store>|<url>"
+    pub price_sources: Vec<String>,
+    // every price found is recorded, the last one is used when no source gives a price
+    pub price_history_path: String,
+    // older last price is not used, the default one is quoted instead
+    pub price_history_max_age_days: i64,
+    // the Braník and goods prices are fetched again every few hours while running, 0 for never
+    pub price_refresh_hours: u64,
+    // and every day at the times, when the new leaflets start
+    pub price_refresh_times: Vec<NaiveTime>,
+    // scraped prices outside of the bounds are never quoted
+    pub min_price: Money,
+    pub max_price: Money,
+    // bigger change from the recent prices waits for a confirmation in the quarantine
+    pub max_price_change_percent: i64,
+    pub price_quarantine_path: String,
+    // accounts never replied to, i.e. other bots quoting amounts
+    pub ignored_authors: Vec<String>,
     pub post_response: bool,
-    pub save_response: bool
+    pub save_response: bool,
+    pub exchange_rates_path: String,
+    pub exchange_rates_max_age_days: i64,
+    pub min_confidence: f32,
+    pub packaging_path: String,
+    pub goods_path: String,
+    // ids of the goods from the catalog replied with besides Braník
+    pub goods: Vec<String>,
+    pub templates_dir: String,
+    // language of the reply templates - "cs", "sk", "en"
+    pub language: String
+}
+
+// problem with a single setting, all of them are reported at once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    // setting key, or the whole line when it can't be read
+    pub key: String,
+    pub message: String
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+// settings of the config file, errors are collected while reading them
+struct Settings<'a, E: Fn(&str) -> Option<String>> {
+    values: HashMap<&'a str, &'a str>,
+    env: E,
+    errors: Vec<ConfigError>
+}
+
+impl<'a, E: Fn(&str) -> Option<String>> Settings<'a, E> {
+    fn error(&mut self, key: &str, message: &str) {
+        self.errors.push(ConfigError { key: key.to_string(), message: message.to_string() });
+    }
+
+    fn required(&mut self, key: &str) -> String {
+        match self.values.get(key) {
+            Some(value) if !value.is_empty() => value.to_string(),
+            _ => {
+                self.error(key, "missing required setting");
+                String::new()
+            }
+        }
+    }
+
+    // environment variable takes precedence over the config file
+    fn secret(&mut self, key: &str, env_variable: &str) -> String {
+        match (self.env)(env_variable) {
+            Some(value) if !value.is_empty() => value,
+            _ => self.required(key)
+        }
+    }
+
+    fn string(&self, key: &str, default: &str) -> String {
+        self.values.get(key).map(|v| v.to_string()).unwrap_or(default.to_string())
+    }
+
+    fn price(&mut self, key: &str, default: Money) -> Money {
+        match self.values.get(key).map(|p| Money::parse(p)) {
+            None => default,
+            Some(Some(price)) if price.is_positive() => price,
+            Some(_) => {
+                self.error(key, "expected positive price, i.e. 39.90");
+                default
+            }
+        }
+    }
+
+    fn parse<T: FromStr>(&mut self, key: &str, default: T, expected: &str) -> T {
+        let Some(value) = self.values.get(key) else {
+            return default;
+        };
+        match value.parse::<T>() {
+            Ok(value) => value,
+            Err(_) => {
+                self.error(key, &format!("expected {}, got '{}'", expected, value));
+                default
+            }
+        }
+    }
 }
 
 impl Config {
-    pub fn load() -> Config {
-        match fs::read_to_string("./.config") {
-            Err(_) => Config::create_default_config(),
-            Ok(text) => Self::read_config_file(&text)
+    pub const PATH: &'static str = "./branik.conf";
+    const POSITIONAL_PATH: &'static str = "./.config";
+    const DEFAULT_REDDIT_URL: &'static str = "https://www.reddit.com";
+    const DEFAULT_REDDIT_OAUTH_URL: &'static str = "https://oauth.reddit.com";
+    const DEFAULT_REDDIT_CLIENT: &'static str = "roux";
+    const REDDIT_CLIENTS: [&'static str; 2] = ["roux", "http"];
+    const DEFAULT_DATA_DIR: &'static str = "./data";
+    const DEFAULT_COMMENTS_PER_POST_LIMIT: usize = 3;
+    const DEFAULT_POLL_INTERVAL_SECONDS: u64 = 60 * 5;
+    const DEFAULT_RUN_DURATION_MINUTES: i64 = 60 * 4;
+    // 39,90 Kč
+    const DEFAULT_PRICE: Money = Money::from_halere(3990);
+    const DEFAULT_EXCHANGE_RATES_FILE: &'static str = "denni_kurz.txt";
+    // CNB doesn't publish rates on weekends and holidays
+    const DEFAULT_EXCHANGE_RATES_MAX_AGE_DAYS: i64 = 4;
+    // values the parser is less sure about are not replied to, i.e. "4k" without any price word around
+    const DEFAULT_MIN_CONFIDENCE: f32 = 0.5;
+    // packaging tiers the amounts are expressed in, built-in ones are used when the file is missing,
+    // other languages than czech have their own files - "packaging_en.txt"
+    const DEFAULT_PACKAGING_FILE: &'static str = "packaging.txt";
+    const DEFAULT_GOODS_FILE: &'static str = "goods.txt";
+    const DEFAULT_PRICE_HISTORY_FILE: &'static str = "price_history.txt";
+    // leaflet sales last a week
+    const DEFAULT_PRICE_HISTORY_MAX_AGE_DAYS: i64 = 7;
+    const DEFAULT_PRICE_REFRESH_HOURS: u64 = 6;
+    // leaflets change at midnight
+    const DEFAULT_PRICE_REFRESH_TIMES: &'static str = "00:05";
+    const DEFAULT_MIN_PRICE: Money = Money::from_halere(1500);
+    const DEFAULT_MAX_PRICE: Money = Money::from_halere(15000);
+    const DEFAULT_MAX_PRICE_CHANGE_PERCENT: i64 = 30;
+    const DEFAULT_PRICE_QUARANTINE_FILE: &'static str = "price_quarantine.txt";
+    const DEFAULT_IGNORED_AUTHORS: &'static str = "AutoModerator, fabia-bot";
+    const DEFAULT_TEMPLATES_DIR: &'static str = "./templates";
+    const DEFAULT_LANGUAGE: &'static str = "cs";
+    const KEYS: [&'static str; 34] = ["user_agent", "client_id", "client_secret", "user_name", "password", "subreddit",
+        "comments_per_post_limit", "default_price", "post_response", "save_response", "exchange_rates_path",
+        "exchange_rates_max_age_days", "min_confidence", "packaging_path", "goods_path", "goods", "templates_dir", "language", "data_dir",
+        "poll_interval_seconds", "run_duration_minutes", "reddit_url", "reddit_oauth_url", "reddit_client", "price_sources", "price_history_path",
+        "price_history_max_age_days", "price_refresh_hours", "price_refresh_times", "min_price", "max_price", "max_price_change_percent",
+        "price_quarantine_path", "ignored_authors"];
+
+    // no config file at all means dry run with placeholder credentials
+    pub fn load(path: &str) -> Result<Config, Vec<ConfigError>> {
+        match fs::read_to_string(path) {
+            Err(_) => {
+                println!("Failed to load config from {}, using default one", path);
+                Ok(Config::create_default_config())
+            },
+            Ok(text) => Self::read_config_file(&text, |variable| env::var(variable).ok())
         }
     }
 
-    fn create_default_config() -> Self {
-        Config { 
+    pub fn create_default_config() -> Self {
+        Config {
             user_agent: "USER_AGENT".to_string(),
             client_id: "CLIENT_ID".to_string(),
             client_secret: "CLIENT_SECRET".to_string(),
             user_name: "USER_NAME".to_string(),
             password: "PASSWORD".to_string(),
             subreddit: "SUBREDDIT".to_string(),
-            comments_per_post_limit: 3,
-            default_price: 39.90,
+            reddit_url: Self::DEFAULT_REDDIT_URL.to_string(),
+            reddit_oauth_url: Self::DEFAULT_REDDIT_OAUTH_URL.to_string(),
+            reddit_client: Self::DEFAULT_REDDIT_CLIENT.to_string(),
+            data_dir: Self::DEFAULT_DATA_DIR.to_string(),
+            comments_per_post_limit: Self::DEFAULT_COMMENTS_PER_POST_LIMIT,
+            poll_interval_seconds: Self::DEFAULT_POLL_INTERVAL_SECONDS,
+            run_duration_minutes: Self::DEFAULT_RUN_DURATION_MINUTES,
+            default_price: Self::DEFAULT_PRICE,
+            price_sources: Self::default_price_sources(),
+            price_history_path: Self::default_data_path(Self::DEFAULT_DATA_DIR, Self::DEFAULT_PRICE_HISTORY_FILE),
+            price_history_max_age_days: Self::DEFAULT_PRICE_HISTORY_MAX_AGE_DAYS,
+            price_refresh_hours: Self::DEFAULT_PRICE_REFRESH_HOURS,
+            price_refresh_times: Self::read_times(Self::DEFAULT_PRICE_REFRESH_TIMES).unwrap_or_default(),
+            min_price: Self::DEFAULT_MIN_PRICE,
+            max_price: Self::DEFAULT_MAX_PRICE,
+            max_price_change_percent: Self::DEFAULT_MAX_PRICE_CHANGE_PERCENT,
+            price_quarantine_path: Self::default_data_path(Self::DEFAULT_DATA_DIR, Self::DEFAULT_PRICE_QUARANTINE_FILE),
+            ignored_authors: Self::read_list(Self::DEFAULT_IGNORED_AUTHORS),
             post_response: false,
-            save_response: false
+            save_response: false,
+            exchange_rates_path: Self::default_data_path(Self::DEFAULT_DATA_DIR, Self::DEFAULT_EXCHANGE_RATES_FILE),
+            exchange_rates_max_age_days: Self::DEFAULT_EXCHANGE_RATES_MAX_AGE_DAYS,
+            min_confidence: Self::DEFAULT_MIN_CONFIDENCE,
+            packaging_path: Self::default_data_path(Self::DEFAULT_DATA_DIR, Self::DEFAULT_PACKAGING_FILE),
+            goods_path: Self::default_data_path(Self::DEFAULT_DATA_DIR, Self::DEFAULT_GOODS_FILE),
+            goods: vec![],
+            templates_dir: Self::DEFAULT_TEMPLATES_DIR.to_string(),
+            language: Self::DEFAULT_LANGUAGE.to_string()
         }
     }
 
-    fn read_config_file(config_str: &str) -> Config {
-        let mut config_lines = config_str.lines();
-        Config {
-            user_agent: config_lines.next().expect("Expected to have user agent on index 0 in the config!").to_string(),
-            client_id: config_lines.next().expect("Expected to have client id on index 1 in the config!").to_string(),
-            client_secret: config_lines.next().expect("Expected to have client secret on index 2 in the config!").to_string(),
-            user_name: config_lines.next().expect("Expected to have username on index 3 in the config!").to_string(),
-            password: config_lines.next().expect("Expected to have password on index 4 in the config!").to_string(),
-            subreddit: config_lines.next().expect("Expected to have subreddit on index 5 in the config!").to_string(),
-            comments_per_post_limit: config_lines.next().expect("Expected to have comments per post limit on index 6 in the config").parse().expect("Expected int here"),
-            default_price: config_lines.next().expect("Expected to have default price on index 8 in the config!").parse::<f32>().expect("(float) XX.XX "),
-            post_response: config_lines.next().expect("Expected to have post response? on index 9 in the config!").to_string().parse::<bool>().expect("Expected (true/false)"),
-            save_response: config_lines.next().expect("Expected to have save response? on index 10 in the config!").parse::<bool>().expect("Expected (true/false)"),
+    // env looks up environment variables, it's passed in to keep the reading testable
+    pub fn read_config_file(config_str: &str, env: impl Fn(&str) -> Option<String>) -> Result<Config, Vec<ConfigError>> {
+        let mut settings = Settings { values: HashMap::new(), env, errors: vec![] };
+        for line in config_str.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let Some((key, value)) = line.split_once('=') else {
+                settings.error(line, "expected 'key = value'");
+                continue;
+            };
+            let key = key.trim();
+            if !Self::KEYS.contains(&key) {
+                settings.error(key, "unknown setting");
+            } else if settings.values.insert(key, value.trim()).is_some() {
+                settings.error(key, "set more than once");
+            }
+        }
+        let data_dir = settings.string("data_dir", Self::DEFAULT_DATA_DIR);
+        let language = settings.string("language", Self::DEFAULT_LANGUAGE);
+        let config = Config {
+            user_agent: settings.required("user_agent"),
+            client_id: settings.secret("client_id", "BRANIK_CLIENT_ID"),
+            client_secret: settings.secret("client_secret", "BRANIK_CLIENT_SECRET"),
+            user_name: settings.required("user_name"),
+            password: settings.secret("password", "BRANIK_PASSWORD"),
+            subreddit: settings.required("subreddit"),
+            reddit_url: settings.string("reddit_url", Self::DEFAULT_REDDIT_URL),
+            reddit_oauth_url: settings.string("reddit_oauth_url", Self::DEFAULT_REDDIT_OAUTH_URL),
+            reddit_client: settings.string("reddit_client", Self::DEFAULT_REDDIT_CLIENT),
+            comments_per_post_limit: settings.parse("comments_per_post_limit", Self::DEFAULT_COMMENTS_PER_POST_LIMIT, "int"),
+            poll_interval_seconds: settings.parse("poll_interval_seconds", Self::DEFAULT_POLL_INTERVAL_SECONDS, "int"),
+            run_duration_minutes: settings.parse("run_duration_minutes", Self::DEFAULT_RUN_DURATION_MINUTES, "int"),
+            default_price: settings.price("default_price", Self::DEFAULT_PRICE),
+            // comma separated as well
+            price_sources: match settings.values.get("price_sources") {
+                None => Self::default_price_sources(),
+                Some(sources) => sources.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+            },
+            price_history_path: settings.string("price_history_path", &Self::default_data_path(&data_dir, Self::DEFAULT_PRICE_HISTORY_FILE)),
+            price_history_max_age_days: settings.parse("price_history_max_age_days", Self::DEFAULT_PRICE_HISTORY_MAX_AGE_DAYS, "int"),
+            price_refresh_hours: settings.parse("price_refresh_hours", Self::DEFAULT_PRICE_REFRESH_HOURS, "int"),
+            // "00:05, 12:00", empty for no daily refresh
+            price_refresh_times: match Self::read_times(&settings.string("price_refresh_times", Self::DEFAULT_PRICE_REFRESH_TIMES)) {
+                Ok(times) => times,
+                Err(time) => {
+                    settings.error("price_refresh_times", &format!("expected times HH:MM, got '{}'", time));
+                    vec![]
+                }
+            },
+            min_price: settings.price("min_price", Self::DEFAULT_MIN_PRICE),
+            max_price: settings.price("max_price", Self::DEFAULT_MAX_PRICE),
+            max_price_change_percent: settings.parse("max_price_change_percent", Self::DEFAULT_MAX_PRICE_CHANGE_PERCENT, "int"),
+            price_quarantine_path: settings.string("price_quarantine_path", &Self::default_data_path(&data_dir, Self::DEFAULT_PRICE_QUARANTINE_FILE)),
+            // comma separated as well
+            ignored_authors: Self::read_list(&settings.string("ignored_authors", Self::DEFAULT_IGNORED_AUTHORS)),
+            post_response: settings.parse("post_response", false, "true/false"),
+            save_response: settings.parse("save_response", false, "true/false"),
+            exchange_rates_path: settings.string("exchange_rates_path", &Self::default_data_path(&data_dir, Self::DEFAULT_EXCHANGE_RATES_FILE)),
+            exchange_rates_max_age_days: settings.parse("exchange_rates_max_age_days", Self::DEFAULT_EXCHANGE_RATES_MAX_AGE_DAYS, "int"),
+            min_confidence: settings.parse("min_confidence", Self::DEFAULT_MIN_CONFIDENCE, "float 0.0 - 1.0"),
+            packaging_path: settings.string("packaging_path", &Self::default_data_path(&data_dir, &Self::language_file(Self::DEFAULT_PACKAGING_FILE, &language))),
+            goods_path: settings.string("goods_path", &Self::default_data_path(&data_dir, &Self::language_file(Self::DEFAULT_GOODS_FILE, &language))),
+            // comma separated - "rohlik, kebab"
+            goods: Self::read_list(&settings.string("goods", "")),
+            templates_dir: settings.string("templates_dir", Self::DEFAULT_TEMPLATES_DIR),
+            language,
+            data_dir
+        };
+        if !(0.0..=1.0).contains(&config.min_confidence) {
+            settings.error("min_confidence", "expected float 0.0 - 1.0");
+        }
+        if config.exchange_rates_max_age_days < 0 {
+            settings.error("exchange_rates_max_age_days", "expected non-negative int");
+        }
+        if config.price_history_max_age_days < 0 {
+            settings.error("price_history_max_age_days", "expected non-negative int");
+        }
+        if !Self::REDDIT_CLIENTS.contains(&config.reddit_client.as_str()) {
+            settings.error("reddit_client", "expected roux or http");
+        }
+        if config.min_price >= config.max_price {
+            settings.error("max_price", "expected price above min_price");
+        }
+        if config.max_price_change_percent < 0 {
+            settings.error("max_price_change_percent", "expected non-negative int");
+        }
+        if let Err(errors) = PriceSources::from_specs(&config.price_sources) {
+            for error in errors {
+                settings.error("price_sources", &error);
+            }
+        }
+        match settings.errors.is_empty() {
+            true => Ok(config),
+            false => Err(settings.errors)
+        }
+    }
+
+    // "rohlik, kebab"
+    fn read_list(list: &str) -> Vec<String> {
+        list.split(',').map(|i| i.trim().to_string()).filter(|i| !i.is_empty()).collect()
+    }
+
+    // comma separated "HH:MM", the invalid time is the error
+    fn read_times(times: &str) -> Result<Vec<NaiveTime>, String> {
+        times.split(',').map(|t| t.trim()).filter(|t| !t.is_empty())
+            .map(|t| NaiveTime::parse_from_str(t, "%H:%M").map_err(|_| t.to_string()))
+            .collect()
+    }
+
+    fn default_price_sources() -> Vec<String> {
+        vec![format!("akcniceny|{}", AkcniCeny::BRANIK_URL)]
+    }
+
+    // file in the data directory - "./data/last_comment"
+    pub fn data_path(&self, file: &str) -> String {
+        Self::default_data_path(&self.data_dir, file)
+    }
+
+    fn default_data_path(data_dir: &str, file: &str) -> String {
+        format!("{}/{}", data_dir.trim_end_matches('/'), file)
+    }
+
+    // "goods.txt" for czech, "goods_en.txt" for english
+    fn language_file(file: &str, language: &str) -> String {
+        match (language, file.rsplit_once('.')) {
+            (Self::DEFAULT_LANGUAGE, _) => file.to_string(),
+            (_, Some((name, extension))) => format!("{}_{}.{}", name, language, extension),
+            (_, None) => format!("{}_{}", file, language)
+        }
+    }
+
+    // the older positional config - a setting per line in the order of KEYS, the last eight lines being optional
+    pub fn migrate_positional_config_text(config_str: &str) -> String {
+        let mut migrated = "# migrated from the positional .config\n".to_string();
+        for (key, value) in Self::KEYS.iter().zip(config_str.lines()) {
+            migrated += &format!("{} = {}\n", key, value.trim());
         }
+        migrated
+    }
+
+    // only the default config is migrated and only when it doesn't exist yet
+    pub fn migrate(path: &str) {
+        if path == Self::PATH && fs::metadata(path).is_err() {
+            Self::migrate_positional_config(Self::POSITIONAL_PATH, path);
+        }
+    }
+
+    // the old file is kept as ".config.old"
+    fn migrate_positional_config(positional_path: &str, path: &str) {
+        let Ok(text) = fs::read_to_string(positional_path) else {
+            return;
+        };
+        println!("Migrating positional config {} to {}", positional_path, path);
+        match fs::write(path, Self::migrate_positional_config_text(&text)) {
+            Err(e) => println!("Failed to write migrated config {}: {}", path, e),
+            Ok(_) => {
+                if let Err(e) = fs::rename(positional_path, format!("{}.old", positional_path)) {
+                    println!("Failed to rename {}: {}", positional_path, e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUIRED: &str = "user_agent = branik-bot/0.1\nclient_id = id\nclient_secret = secret\nuser_name = branik-bot\npassword = heslo\nsubreddit = czech\n";
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_read_config_file() {
+        let config = Config::read_config_file(&format!("# comment\n{}default_price = 42,90\ngoods = rohlik, kebab\nlanguage = sk", REQUIRED), no_env).unwrap();
+        assert_eq!("czech", config.subreddit);
+        assert_eq!(Money::from_halere(4290), config.default_price);
+        assert_eq!(vec!["rohlik".to_string(), "kebab".to_string()], config.goods);
+        assert_eq!("sk", config.language);
+        // defaults of the optional settings
+        assert_eq!(3, config.comments_per_post_limit);
+        assert!(!config.post_response);
+        assert_eq!(0.5, config.min_confidence);
+        assert_eq!("./data/packaging_sk.txt", config.packaging_path);
+        assert_eq!("./data/goods_sk.txt", config.goods_path);
+        assert_eq!(vec![format!("akcniceny|{}", AkcniCeny::BRANIK_URL)], config.price_sources);
+        assert_eq!(vec!["AutoModerator".to_string(), "fabia-bot".to_string()], config.ignored_authors);
+        let config = Config::read_config_file(&format!("{}ignored_authors = RemindMeBot,fabia-bot", REQUIRED), no_env).unwrap();
+        assert_eq!(vec!["RemindMeBot".to_string(), "fabia-bot".to_string()], config.ignored_authors);
+    }
+
+    #[test]
+    fn test_price_sources() {
+        let config = Config::read_config_file(&format!("{}price_sources = kupi|https://www.kupi.cz/branik, shop:Albert|https://www.albert.cz/branik", REQUIRED), no_env).unwrap();
+        assert_eq!(vec!["kupi|https://www.kupi.cz/branik".to_string(), "shop:Albert|https://www.albert.cz/branik".to_string()], config.price_sources);
+        let errors = Config::read_config_file(&format!("{}price_sources = letak|https://letak.cz", REQUIRED), no_env).err().unwrap();
+        assert_eq!("price_sources: Unknown price source 'letak'", errors[0].to_string());
+    }
+
+    #[test]
+    fn test_price_refresh() {
+        let config = Config::read_config_file(REQUIRED, no_env).unwrap();
+        assert_eq!((6, vec![NaiveTime::from_hms_opt(0, 5, 0).unwrap()]), (config.price_refresh_hours, config.price_refresh_times));
+        let config = Config::read_config_file(&format!("{}price_refresh_hours = 0\nprice_refresh_times = 00:10, 12:00", REQUIRED), no_env).unwrap();
+        assert_eq!(0, config.price_refresh_hours);
+        assert_eq!(vec![NaiveTime::from_hms_opt(0, 10, 0).unwrap(), NaiveTime::from_hms_opt(12, 0, 0).unwrap()], config.price_refresh_times);
+        let config = Config::read_config_file(&format!("{}price_refresh_times =", REQUIRED), no_env).unwrap();
+        assert!(config.price_refresh_times.is_empty());
+        let errors = Config::read_config_file(&format!("{}price_refresh_times = pulnoc", REQUIRED), no_env).err().unwrap();
+        assert_eq!("price_refresh_times: expected times HH:MM, got 'pulnoc'", errors[0].to_string());
+    }
+
+    #[test]
+    fn test_price_history_max_age() {
+        assert_eq!(7, Config::read_config_file(REQUIRED, no_env).unwrap().price_history_max_age_days);
+        let config = Config::read_config_file(&format!("{}price_history_max_age_days = 2", REQUIRED), no_env).unwrap();
+        assert_eq!(2, config.price_history_max_age_days);
+        let errors = Config::read_config_file(&format!("{}price_history_max_age_days = -1", REQUIRED), no_env).err().unwrap();
+        assert_eq!("price_history_max_age_days: expected non-negative int", errors[0].to_string());
+    }
+
+    #[test]
+    fn test_price_bounds() {
+        let config = Config::read_config_file(&format!("{}min_price = 20\nmax_price = 80,50\nmax_price_change_percent = 25", REQUIRED), no_env).unwrap();
+        assert_eq!((Money::from_units(20), Money::from_halere(8050), 25), (config.min_price, config.max_price, config.max_price_change_percent));
+        assert_eq!("./data/price_quarantine.txt", config.price_quarantine_path);
+        let errors = Config::read_config_file(&format!("{}min_price = 90\nmax_price = 80\nmax_price_change_percent = -5", REQUIRED), no_env).err().unwrap();
+        let keys: Vec<&str> = errors.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(vec!["max_price", "max_price_change_percent"], keys);
+    }
+
+    #[test]
+    fn test_reddit_client() {
+        let config = Config::read_config_file(REQUIRED, no_env).unwrap();
+        assert_eq!("roux", config.reddit_client);
+        let config = Config::read_config_file(&format!("{}reddit_client = http", REQUIRED), no_env).unwrap();
+        assert_eq!("http", config.reddit_client);
+        let errors = Config::read_config_file(&format!("{}reddit_client = praw", REQUIRED), no_env).err().unwrap();
+        assert_eq!("reddit_client: expected roux or http", errors[0].to_string());
+    }
+
+    #[test]
+    fn test_data_dir() {
+        let config = Config::read_config_file(&format!("{}data_dir = /var/branik/
+goods_path = ./goods.txt", REQUIRED), no_env).unwrap();
+        assert_eq!("/var/branik/denni_kurz.txt", config.exchange_rates_path);
+        assert_eq!("/var/branik/packaging.txt", config.packaging_path);
+        assert_eq!("/var/branik/price_history.txt", config.price_history_path);
+        assert_eq!("./goods.txt", config.goods_path);
+        assert_eq!("/var/branik/last_comment", config.data_path("last_comment"));
+    }
+
+    #[test]
+    fn test_env_secrets() {
+        let env = |variable: &str| match variable {
+            "BRANIK_PASSWORD" => Some("tajne".to_string()),
+            "BRANIK_CLIENT_SECRET" => Some("env-secret".to_string()),
+            _ => None
+        };
+        let config = Config::read_config_file("user_agent = a\nclient_id = id\nuser_name = u\nsubreddit = czech\npassword = heslo", env).unwrap();
+        assert_eq!("tajne", config.password);
+        assert_eq!("env-secret", config.client_secret);
+        assert_eq!("id", config.client_id);
+    }
+
+    #[test]
+    fn test_all_errors_reported() {
+        let errors = Config::read_config_file("user_agent = a\nuser_agent = b\nclient_id = id\nuser_name = u\nsubreddit =\nbarva = modra\nspatny radek\npost_response = ano\ndefault_price = zadarmo\nmin_confidence = 2", no_env).err().unwrap();
+        let keys: Vec<&str> = errors.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(vec!["user_agent", "barva", "spatny radek", "client_secret", "password", "subreddit", "default_price", "post_response", "min_confidence"], keys);
+        assert_eq!("post_response: expected true/false, got 'ano'", errors[7].to_string());
+    }
+
+    #[test]
+    fn test_migrate_positional_config() {
+        let positional = "agent\nid\nsecret\nuser\nheslo\nczech\n2\n45.90\ntrue\nfalse\n./kurz.txt";
+        let config = Config::read_config_file(&Config::migrate_positional_config_text(positional), no_env).unwrap();
+        assert_eq!("agent", config.user_agent);
+        assert_eq!("heslo", config.password);
+        assert_eq!(2, config.comments_per_post_limit);
+        assert_eq!(Money::from_halere(4590), config.default_price);
+        assert!(config.post_response);
+        assert!(!config.save_response);
+        assert_eq!("./kurz.txt", config.exchange_rates_path);
+        assert_eq!(4, config.exchange_rates_max_age_days);
     }
 }
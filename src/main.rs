@@ -1,21 +1,106 @@
-use std::thread;
+use std::{env, fs, process, thread};
 
 use bot::BranikBot;
+use chrono::{Days, Local, NaiveDate};
+use cli::{Cli, Command};
+use config::Config;
+use reddit::{http::HttpReddit, roux_client::RouxReddit};
 use tokio::time;
 
 pub mod bot;
+pub mod cli;
 pub mod comment_reader;
 pub mod config;
+pub mod money;
 pub mod parser;
+pub mod rates;
+pub mod reddit;
+pub mod replay;
 
 #[tokio::main]
 async fn main() {
+    let cli = match Cli::parse(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(error) => {
+            println!("{}\n\n{}", error, Cli::USAGE);
+            process::exit(2);
+        }
+    };
+    Config::migrate(&cli.config_path);
+    match cli.command {
+        Command::Run { dry_run } => run(&cli.config_path, dry_run).await,
+        Command::Parse(text) => parse(&cli.config_path, &text).await,
+        Command::Price => price(&cli.config_path).await,
+        Command::History { from, to } => history(&cli.config_path, from, to).await,
+        Command::Replay { input, output } => replay(&cli.config_path, &input, &output).await
+    }
+}
+
+async fn run(config_path: &str, dry_run: bool) {
     loop {
-        let mut bot = BranikBot::respawn().await;
-        let _ = bot.run().await;
-        drop(bot);
+        let config = bot::load_config(config_path, dry_run);
+        // roux always talks to reddit.com, the configured urls are used only by the http client
+        match config.reddit_client.as_str() {
+            "http" => {
+                let reddit = HttpReddit::connect(&config).await;
+                BranikBot::new(config, reddit).run().await
+            },
+            _ => {
+                let reddit = RouxReddit::login(&config).await;
+                BranikBot::new(config, reddit).run().await
+            }
+        }
         println!("Restart in 10 seconds");
         thread::sleep(time::Duration::from_secs(10));
     }
 }
 
+// offline, the reply uses the default price from the config
+async fn parse(config_path: &str, text: &str) {
+    let bot = BranikBot::respawn(config_path, true).await;
+    let results = bot.parse_comment(text);
+    if results.is_empty() {
+        println!("Nothing found");
+    }
+    for result in results.iter() {
+        let skipped = if bot.can_generate_result_row(result) { "" } else { " - not replied to" };
+        println!("{}{}", result, skipped);
+    }
+    if let Some(response) = bot.generate_response(text) {
+        println!("\n{}", response);
+    }
+}
+
+async fn price(config_path: &str) {
+    let bot = BranikBot::respawn(config_path, true).await;
+    bot.update_price().await;
+    bot.print_prices();
+}
+
+async fn history(config_path: &str, from: Option<NaiveDate>, to: Option<NaiveDate>) {
+    let bot = BranikBot::respawn(config_path, true).await;
+    let to = to.unwrap_or(Local::now().date_naive());
+    let from = from.unwrap_or(to - Days::new(30));
+    bot.print_price_history(from, to);
+}
+
+// offline as well, replies and reasons for the skipped comments are written as JSON lines
+async fn replay(config_path: &str, input: &str, output: &str) {
+    let dump = match fs::read_to_string(input) {
+        Ok(dump) => dump,
+        Err(e) => {
+            println!("Failed to read comments from {}: {}", input, e);
+            process::exit(1);
+        }
+    };
+    let bot = BranikBot::respawn(config_path, true).await;
+    let comments = replay::read_dump(&dump);
+    let outcomes = bot.replay(&comments);
+    let lines: Vec<String> = comments.iter().zip(outcomes.iter()).map(|(c, o)| replay::outcome_line(c, o)).collect();
+    if let Err(e) = fs::write(output, lines.join("\n") + "\n") {
+        println!("Failed to write replies to {}: {}", output, e);
+        process::exit(1);
+    }
+    let replied = outcomes.iter().filter(|o| o.is_ok()).count();
+    println!("Replied to {} of {} comments, written to {}", replied, comments.len(), output);
+}
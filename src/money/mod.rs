@@ -0,0 +1,152 @@
+use std::fmt;
+
+// Exact amount of money in haléře (hundredths of the currency unit). Parsed values, prices and converted
+// amounts are all kept in it, so "3.000.000 kc" or "1,5 mega" don't lose precision as they would in f32.
+// Arithmetic never overflows - it either returns None or saturates for absurd amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Money {
+    halere: i64
+}
+
+impl Money {
+    pub const ZERO: Money = Money { halere: 0 };
+    const HALERE_PER_UNIT: i64 = 100;
+
+    pub const fn from_halere(halere: i64) -> Money {
+        Money { halere }
+    }
+
+    pub const fn from_units(units: i64) -> Money {
+        Money { halere: units.saturating_mul(Self::HALERE_PER_UNIT) }
+    }
+
+    // amount computed in floating point, i.e. slang words or exchange rate conversion
+    pub fn from_f64(units: f64) -> Option<Money> {
+        let halere = (units * Self::HALERE_PER_UNIT as f64).round();
+        // i64::MAX as f64 is rounded up, so it already doesn't fit
+        if !halere.is_finite() || halere >= i64::MAX as f64 || halere < i64::MIN as f64 {
+            return None;
+        }
+        Some(Money { halere: halere as i64 })
+    }
+
+    // amount written by people or read from config - "39.90", "39,9" or "40"
+    pub fn parse(value: &str) -> Option<Money> {
+        Self::parse_multiplied(&value.trim().replace(',', "."), 1)
+    }
+
+    // value with "." as the only decimal delimiter and no thousands separators multiplied by the unit
+    // before rounding to haléře, i.e. "1.2345" with multiplier 1000 (1.2345k) is exactly 1234,50
+    pub fn parse_multiplied(value: &str, multiplier: i64) -> Option<Money> {
+        let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+        let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return None;
+        }
+        let digits = format!("{}{}", whole, fraction).parse::<i128>().ok()?;
+        let scale = 10i128.checked_pow(fraction.len() as u32)?;
+        let halere = digits.checked_mul(Self::HALERE_PER_UNIT as i128)?.checked_mul(multiplier as i128)?;
+        // round half up to whole haléře
+        let halere = (halere + scale / 2) / scale;
+        i64::try_from(halere).ok().map(Money::from_halere)
+    }
+
+    pub fn halere(&self) -> i64 {
+        self.halere
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.halere > 0
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Option<Money> {
+        self.halere.checked_mul(factor).map(Money::from_halere)
+    }
+
+    // multiplies by the fraction, rounding down, i.e. monthly amount to daily one is 12/365
+    pub fn mul_ratio(self, numerator: i64, denominator: i64) -> Money {
+        if denominator == 0 {
+            return self;
+        }
+        let halere = self.halere as i128 * numerator as i128 / denominator as i128;
+        Money { halere: halere.clamp(i64::MIN as i128, i64::MAX as i128) as i64 }
+    }
+
+    // converts the amount with exchange rate - CZK for single unit of foreign currency
+    pub fn convert(self, rate: f64) -> Option<Money> {
+        Self::from_f64(self.halere as f64 * rate / Self::HALERE_PER_UNIT as f64)
+    }
+
+    // how many whole items for the price can be bought for the amount
+    pub fn count(self, price: Money) -> u64 {
+        if !price.is_positive() || !self.is_positive() {
+            return 0;
+        }
+        (self.halere / price.halere) as u64
+    }
+}
+
+// czech format with space as thousands separator and two decimals - "1 299,90"
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let halere = self.halere.unsigned_abs();
+        let units = (halere / Self::HALERE_PER_UNIT as u64).to_string();
+        let mut grouped = String::with_capacity(units.len() + units.len() / 3);
+        for (index, digit) in units.chars().enumerate() {
+            if index > 0 && (units.len() - index).is_multiple_of(3) {
+                grouped.push(' ');
+            }
+            grouped.push(digit);
+        }
+        let sign = if self.halere < 0 { "-" } else { "" };
+        write!(f, "{}{},{:02}", sign, grouped, halere % Self::HALERE_PER_UNIT as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Some(Money::from_halere(3990)), Money::parse("39.90"));
+        assert_eq!(Some(Money::from_halere(3990)), Money::parse("39,9"));
+        assert_eq!(Some(Money::from_units(40)), Money::parse("40"));
+        assert_eq!(Some(Money::from_halere(50)), Money::parse(".5"));
+        assert_eq!(Some(Money::from_halere(1235)), Money::parse("12.345"));
+        assert_eq!(None, Money::parse("1.000.000"));
+        assert_eq!(None, Money::parse("abc"));
+        assert_eq!(None, Money::parse(""));
+        assert_eq!(None, Money::parse("99999999999999999999999"));
+    }
+
+    #[test]
+    fn test_parse_multiplied() {
+        assert_eq!(Some(Money::from_units(1500)), Money::parse_multiplied("1.5", 1000));
+        assert_eq!(Some(Money::from_halere(123450)), Money::parse_multiplied("1.2345", 1000));
+        assert_eq!(Some(Money::from_units(3_500_000)), Money::parse_multiplied("3.5", 1_000_000));
+        assert_eq!(None, Money::parse_multiplied("999999999999999999", 1_000_000));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(Money::from_units(100), Money::from_units(700).mul_ratio(1, 7));
+        assert_eq!(Money::from_units(1200), Money::from_units(36500).mul_ratio(12, 365));
+        assert_eq!(Money::from_halere(i64::MAX), Money::from_halere(i64::MAX / 2).mul_ratio(8, 1));
+        assert_eq!(None, Money::from_halere(i64::MAX / 2).checked_mul(8));
+        assert_eq!(Some(Money::from_units(245)), Money::from_units(10).convert(24.5));
+        assert_eq!(None, Money::from_halere(i64::MAX).convert(25.0));
+        assert_eq!(12, Money::from_units(500).count(Money::from_halere(3990)));
+        assert_eq!(0, Money::from_units(500).count(Money::ZERO));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("39,90", Money::from_halere(3990).to_string());
+        assert_eq!("0,05", Money::from_halere(5).to_string());
+        assert_eq!("1 299,90", Money::from_halere(129990).to_string());
+        assert_eq!("3 000 000,00", Money::from_units(3_000_000).to_string());
+        assert_eq!("-100,00", Money::from_units(-100).to_string());
+        assert_eq!("999,00", Money::from_units(999).to_string());
+    }
+}
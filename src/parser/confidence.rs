@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+// Bare "k" suffix doesn't have to mean thousands of crowns - "uběhl jsem 10k", "4k televize", "1440p vs 2k".
+// Confidence that such value is money is guessed from the words around it. The same goes for the slang
+// "litr" and "meloun" - "vypil jsem litr" is not 1000 Kč.
+
+// values without "k" suffix have explicit currency and are always money
+pub const CERTAIN: f32 = 1.0;
+const BARE_K: f32 = 0.6;
+const NEARBY_WORDS: usize = 4;
+const MONEY_WORD_WEIGHT: f32 = 0.3;
+const OTHER_WORD_WEIGHT: f32 = -0.5;
+const RESOLUTION_WEIGHT: f32 = -0.2;
+// ambiguous slang without any price word around
+const UNSURE_SLANG: f32 = 0.3;
+
+const MONEY_WORDS: [&str; 13] = ["kč", "kc", "czk", "korun", "koruny", "mzda", "mzdu", "hrubého", "hrubeho", "čistého", "cisteho", "ročně", "měsíčně"];
+const MONEY_WORD_PREFIXES: [&str; 17] = ["cen", "stoj", "stál", "zaplat", "plat", "výplat", "vyplat", "nájem", "najem", "nájm", "najm",
+    "koupil", "prodal", "prodám", "prodam", "kupuj", "dražš"];
+const OTHER_WORDS: [&str; 10] = ["km", "tv", "hz", "fps", "hdr", "run", "1080p", "1440p", "2160p", "720p"];
+const OTHER_WORD_PREFIXES: [&str; 19] = ["kilomet", "běh", "beh", "uběh", "ubeh", "běž", "maraton", "půlmaraton", "pulmaraton", "krok",
+    "monitor", "rozlišen", "rozlisen", "televiz", "displej", "obrazov", "sledujíc", "odběratel", "zhlédnut"];
+// common resolutions that are rarely meant as money on their own
+const RESOLUTIONS: [&str; 3] = ["2k", "4k", "8k"];
+const AMBIGUOUS_SLANG_PREFIXES: [&str; 2] = ["litr", "melou"];
+const CURRENCY_WORDS: [&str; 6] = ["kč", "kc", "czk", "korun", "koruny", "korunu"];
+const PRICE_WORD_PREFIXES: [&str; 5] = ["stál", "stal", "stoj", "plat", "zaplat"];
+
+// expects lowercase text, span of the value and span of the sentence around it
+pub fn money_confidence(text: &str, span: &Range<usize>, sentence: &Range<usize>) -> f32 {
+    let value_text = &text[span.clone()];
+    if value_text.split_whitespace().last().is_some_and(|w| AMBIGUOUS_SLANG_PREFIXES.iter().any(|p| w.starts_with(p))) {
+        return slang_confidence(text, span, sentence);
+    }
+    if !value_text.ends_with('k') {
+        return CERTAIN;
+    }
+    let words_before = text[sentence.start.min(span.start)..span.start].split_whitespace().rev().take(NEARBY_WORDS);
+    let words_after = text[span.end..sentence.end.max(span.end)].split_whitespace().take(NEARBY_WORDS);
+    let mut confidence = BARE_K;
+    if RESOLUTIONS.contains(&value_text) {
+        confidence += RESOLUTION_WEIGHT;
+    }
+    // the closest word telling what the value is wins - "4k televizi za 12k"
+    let mut closest: Option<(usize, f32)> = None;
+    let words_before = words_before.enumerate().map(|(i, w)| (i + 1, w, true));
+    let words_after = words_after.enumerate().map(|(i, w)| (i + 1, w, false));
+    for (distance, word, is_before) in words_before.chain(words_after) {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let Some(weight) = word_weight(word, distance == 1 && is_before) else {
+            continue;
+        };
+        closest = match closest {
+            Some((closest_distance, closest_weight)) if closest_distance == distance => Some((distance, closest_weight + weight)),
+            Some((closest_distance, _)) if closest_distance < distance => closest,
+            _ => Some((distance, weight))
+        };
+    }
+    if let Some((_, weight)) = closest {
+        confidence += weight;
+    }
+    confidence.clamp(0.0, CERTAIN)
+}
+
+// "dal jsem za to litr" or "litr to stál"
+fn slang_confidence(text: &str, span: &Range<usize>, sentence: &Range<usize>) -> f32 {
+    let words_before = text[sentence.start.min(span.start)..span.start].split_whitespace().rev();
+    let words_after = text[span.end..sentence.end.max(span.end)].split_whitespace();
+    match has_price_word(words_before, true) || has_price_word(words_after, false) {
+        true => CERTAIN,
+        false => UNSURE_SLANG
+    }
+}
+
+fn has_price_word<'a>(words: impl Iterator<Item = &'a str>, is_before: bool) -> bool {
+    words.map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        // words behind another amount belong to it - "litr rumu za 300 kč"
+        .take_while(|w| !w.starts_with(|c: char| c.is_ascii_digit()))
+        .take(NEARBY_WORDS)
+        .any(|w| (is_before && w == "za") || CURRENCY_WORDS.contains(&w) || PRICE_WORD_PREFIXES.iter().any(|p| w.starts_with(p)))
+}
+
+fn word_weight(word: &str, is_right_before: bool) -> Option<f32> {
+    let is_money_word = MONEY_WORDS.contains(&word) || MONEY_WORD_PREFIXES.iter().any(|p| word.starts_with(p))
+        // "za 5k" but not "10k za hodinu"
+        || (is_right_before && word == "za");
+    let is_other_word = OTHER_WORDS.contains(&word) || OTHER_WORD_PREFIXES.iter().any(|p| word.starts_with(p));
+    match (is_money_word, is_other_word) {
+        (true, false) => Some(MONEY_WORD_WEIGHT),
+        (false, true) => Some(OTHER_WORD_WEIGHT),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn confidence(text: &str, value: &str) -> f32 {
+        let start = text.find(value).unwrap();
+        money_confidence(text, &(start..start + value.len()), &(0..text.len()))
+    }
+
+    #[test]
+    fn test_money_confidence() {
+        assert_eq!(CERTAIN, confidence("stoji to 500 kč", "500 kč"));
+        assert_eq!(BARE_K, confidence("no a co 5k", "5k"));
+        assert!(confidence("zaplatil jsem za to 5k", "5k") > BARE_K);
+        assert!(confidence("plat 45k hrubého", "45k") > BARE_K);
+        assert!(confidence("uběhl jsem 10k za hodinu", "10k") < 0.5);
+        assert!(confidence("mam 4k televizi", "4k") < 0.5);
+        assert!(confidence("1440p vs 2k rozlišení", "2k") < 0.5);
+        assert!(confidence("monitor 4k", "4k") < 0.5);
+        assert!(confidence("4k", "4k") < BARE_K);
+        assert!(confidence("běžel jsem 5k", "5k") < 0.5);
+    }
+
+    #[test]
+    fn test_slang_confidence() {
+        assert_eq!(CERTAIN, confidence("dal jsem za to litr", "litr"));
+        assert_eq!(CERTAIN, confidence("barak stal 5 melounů", "5 melounů"));
+        assert_eq!(CERTAIN, confidence("litr to stálo", "litr"));
+        assert_eq!(CERTAIN, confidence("stoji to 500 kč", "500 kč"));
+        assert_eq!(UNSURE_SLANG, confidence("vypil jsem litr", "litr"));
+        assert_eq!(UNSURE_SLANG, confidence("koupil jsem litr rumu za 300 kc", "litr"));
+        assert_eq!(UNSURE_SLANG, confidence("má 5 melounů na zahradě", "5 melounů"));
+    }
+}
@@ -0,0 +1,63 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Currency {
+    Eur,
+    Usd,
+    Gbp,
+    Pln
+}
+
+impl Currency {
+    pub const ALL: [Currency; 4] = [Currency::Eur, Currency::Usd, Currency::Gbp, Currency::Pln];
+
+    // ISO 4217 code, used in the reply and in the rate table
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Eur => "EUR",
+            Currency::Usd => "USD",
+            Currency::Gbp => "GBP",
+            Currency::Pln => "PLN"
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Currency> {
+        Self::ALL.into_iter().find(|c| c.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    // maps lowercased symbol, code or czech word matched by the parser to currency
+    pub fn from_token(token: &str) -> Option<Currency> {
+        match token {
+            "€" | "eur" | "eura" | "euro" | "eur." => Some(Currency::Eur),
+            "$" | "usd" => Some(Currency::Usd),
+            "£" | "gbp" => Some(Currency::Gbp),
+            "zł" | "zl" | "pln" => Some(Currency::Pln),
+            t if t.starts_with("dolar") => Some(Currency::Usd),
+            t if t.starts_with("lib") => Some(Currency::Gbp),
+            t if t.starts_with("zlot") || t.starts_with("złot") => Some(Currency::Pln),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_token() {
+        assert_eq!(Some(Currency::Eur), Currency::from_token("€"));
+        assert_eq!(Some(Currency::Eur), Currency::from_token("eura"));
+        assert_eq!(Some(Currency::Usd), Currency::from_token("$"));
+        assert_eq!(Some(Currency::Usd), Currency::from_token("dolarů"));
+        assert_eq!(Some(Currency::Gbp), Currency::from_token("liber"));
+        assert_eq!(Some(Currency::Pln), Currency::from_token("zlotých"));
+        assert_eq!(Some(Currency::Pln), Currency::from_token("zł"));
+        assert_eq!(None, Currency::from_token("kč"));
+    }
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(Some(Currency::Gbp), Currency::from_code("GBP"));
+        assert_eq!(Some(Currency::Pln), Currency::from_code(" pln"));
+        assert_eq!(None, Currency::from_code("JPY"));
+    }
+}
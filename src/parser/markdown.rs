@@ -0,0 +1,244 @@
+use std::ops::Range;
+
+// Author's own prose extracted from reddit markdown comment body. Block quotes, code, link targets,
+// urls and bot footers are dropped, html entities decoded and every byte of the text remembers
+// its position in the original body.
+pub struct Prose {
+    pub text: String,
+    // source of every byte of the text in the original body, i.e. "&amp;" for "&"
+    sources: Vec<Range<usize>>
+}
+
+impl Prose {
+    const ENTITIES: [(&'static str, char); 7] = [
+        ("&amp;", '&'),
+        ("&lt;", '<'),
+        ("&gt;", '>'),
+        ("&quot;", '"'),
+        ("&apos;", '\''),
+        ("&#39;", '\''),
+        ("&nbsp;", '\u{a0}')
+    ];
+
+    const URL_PREFIXES: [&'static str; 5] = ["http://", "https://", "www.", "/r/", "/u/"];
+
+    // footers of bots quoting amounts, i.e. "^(Jsem bot, ...)" or "*I am a bot, and this action..."
+    const BOT_FOOTERS: [&'static str; 3] = ["jsem bot", "i am a bot", "i'm a bot"];
+
+    pub fn from_markdown(body: &str) -> Prose {
+        let mut prose = Prose { text: String::new(), sources: vec![] };
+        let mut fence: Option<&str> = None;
+        let mut previous_blank = true;
+        let mut in_indented_code = false;
+        let mut line_start = 0;
+        for line in body.split_inclusive('\n') {
+            let trimmed = line.trim_start();
+            let start = line_start;
+            line_start += line.len();
+            // fenced code block - ``` or ~~~
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+                prose.push('\n', start..start);
+                continue;
+            }
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fence = Some(&trimmed[..3]);
+                prose.push('\n', start..start);
+                continue;
+            }
+            let is_blank = trimmed.is_empty();
+            // indented code block has to follow blank line or another indented code line
+            in_indented_code = !is_blank && (line.starts_with("    ") || line.starts_with('\t')) && (previous_blank || in_indented_code);
+            previous_blank = is_blank;
+            if in_indented_code || Self::is_quote(trimmed) || Self::is_bot_footer(trimmed) {
+                prose.push('\n', start..start);
+                continue;
+            }
+            prose.push_inline(line, start);
+        }
+        prose
+    }
+
+    // maps range in the prose text to range in the original body
+    pub fn original_range(&self, range: &Range<usize>) -> Range<usize> {
+        if range.is_empty() {
+            let start = self.sources.get(range.start).map(|s| s.start).unwrap_or(0);
+            return start..start;
+        }
+        self.sources[range.start].start..self.sources[range.end - 1].end
+    }
+
+    fn is_quote(line: &str) -> bool {
+        // ">!spoiler!<" is author's text, not a quote
+        (line.starts_with('>') && !line.starts_with(">!"))
+            || (line.starts_with("&gt;") && !line.starts_with("&gt;!"))
+    }
+
+    fn is_bot_footer(line: &str) -> bool {
+        let line = line.to_lowercase();
+        (line.starts_with('^') || line.starts_with('*') || line.starts_with('_'))
+            && Self::BOT_FOOTERS.iter().any(|f| line.contains(f))
+    }
+
+    // "[" followed by the text and the target on the same line
+    fn is_link_start(rest: &str) -> bool {
+        let Some(close) = rest.find("](") else {
+            return false;
+        };
+        !rest[1..close].contains('[') && Self::find_closing_parenthesis(&rest[close + 1..]).is_some()
+    }
+
+    fn push(&mut self, c: char, source: Range<usize>) {
+        self.text.push(c);
+        for _ in 0..c.len_utf8() {
+            self.sources.push(source.clone());
+        }
+    }
+
+    fn push_inline(&mut self, line: &str, line_start: usize) {
+        let mut index = 0;
+        while index < line.len() {
+            let rest = &line[index..];
+            let offset = line_start + index;
+            let c = rest.chars().next().unwrap();
+            // escaped markdown char - "\*"
+            if c == '\\' {
+                if let Some(escaped) = rest[1..].chars().next() {
+                    self.push(escaped, offset..offset + 1 + escaped.len_utf8());
+                    index += 1 + escaped.len_utf8();
+                    continue;
+                }
+            }
+            // inline code - `500kc` or ``code``
+            if c == '`' {
+                let ticks = rest.len() - rest.trim_start_matches('`').len();
+                if let Some(end) = rest[ticks..].find(&rest[..ticks]) {
+                    index += ticks + end + ticks;
+                    continue;
+                }
+            }
+            // opening bracket of the link text - [text](https://shop.cz/)
+            if c == '[' && Self::is_link_start(rest) {
+                index += 1;
+                continue;
+            }
+            // link target - [text](https://shop.cz/produkt-500kc/)
+            if rest.starts_with("](") {
+                if let Some(end) = Self::find_closing_parenthesis(&rest[1..]) {
+                    index += 1 + end + 1;
+                    continue;
+                }
+            }
+            // bare url or reddit path
+            let is_word_start = index == 0 || line[..index].ends_with(|c: char| c.is_whitespace() || c == '(');
+            if is_word_start && Self::URL_PREFIXES.iter().any(|p| rest.starts_with(p)) {
+                index += rest.find(char::is_whitespace).unwrap_or(rest.len());
+                continue;
+            }
+            // spoiler markers - >!text!<
+            if rest.starts_with(">!") || rest.starts_with("!<") {
+                index += 2;
+                continue;
+            }
+            if rest.starts_with("&gt;!") {
+                index += 5;
+                continue;
+            }
+            if rest.starts_with("!&lt;") {
+                index += 5;
+                continue;
+            }
+            if c == '&' {
+                if let Some((entity, decoded)) = Self::decode_entity(rest) {
+                    if decoded != '\u{200b}' {
+                        self.push(decoded, offset..offset + entity);
+                    }
+                    index += entity;
+                    continue;
+                }
+            }
+            // emphasis, strikethrough and superscript markers
+            if c == '*' || c == '^' || rest.starts_with("~~") {
+                index += if c == '~' { 2 } else { 1 };
+                continue;
+            }
+            self.push(c, offset..offset + c.len_utf8());
+            index += c.len_utf8();
+        }
+    }
+
+    // returns index of the parenthesis closing the one at the start of the text
+    fn find_closing_parenthesis(text: &str) -> Option<usize> {
+        let mut depth = 0;
+        for (index, c) in text.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index);
+                    }
+                },
+                c if c.is_whitespace() => return None,
+                _ => ()
+            }
+        }
+        None
+    }
+
+    // returns length of the entity in the text and decoded char
+    fn decode_entity(text: &str) -> Option<(usize, char)> {
+        if let Some((entity, c)) = Self::ENTITIES.iter().find(|(e, _)| text.starts_with(e)) {
+            return Some((entity.len(), *c));
+        }
+        let end = text.find(';').filter(|e| *e <= 10)?;
+        let code = text[1..end].strip_prefix('#')?;
+        let code = match code.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => code.parse::<u32>().ok()?
+        };
+        Some((end + 1, char::from_u32(code)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_quotes_and_code() {
+        let body = "&gt; Za 500 kc bych to nebral\n> 600 kc\n\nJa bych dal `700kc` a\n\n    800 kc\n\n```\n900 kc\n```\nmax 100 kc";
+        let prose = Prose::from_markdown(body);
+        assert!(!prose.text.contains("500"));
+        assert!(!prose.text.contains("600"));
+        assert!(!prose.text.contains("700"));
+        assert!(!prose.text.contains("800"));
+        assert!(!prose.text.contains("900"));
+        assert!(prose.text.contains("Ja bych dal  a"));
+        assert!(prose.text.contains("max 100 kc"));
+    }
+
+    #[test]
+    fn test_skip_links_and_decode_entities() {
+        let body = "Tady [obchod](https://shop.cz/produkt-500kc/) a https://shop.cz/600kc &amp; ^(Jsem) >!spoiler 200 kc!< **300 kc**";
+        let prose = Prose::from_markdown(body);
+        assert_eq!("Tady obchod a  & (Jsem) spoiler 200 kc 300 kc", prose.text);
+        assert_eq!("Tohle [neni odkaz] ", Prose::from_markdown("Tohle [neni odkaz] ").text);
+        let prose = Prose::from_markdown("Nazor\n\n^(Jsem bot, 500 kc je 12 dvoulitrovek)");
+        assert!(!prose.text.contains("500"));
+    }
+
+    #[test]
+    fn test_original_range() {
+        let body = "&gt; citace\n**Cena** je &amp; 300 kč";
+        let prose = Prose::from_markdown(body);
+        let start = prose.text.find("300 kč").unwrap();
+        let range = prose.original_range(&(start..start + "300 kč".len()));
+        assert_eq!("300 kč", &body[range]);
+        let start = prose.text.find('&').unwrap();
+        let range = prose.original_range(&(start..start + 1));
+        assert_eq!("&amp;", &body[range]);
+    }
+}
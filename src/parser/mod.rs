@@ -1,46 +1,126 @@
-use regex::Regex;
+use std::{fmt, ops::Range};
+
+use regex::{Regex, Captures};
+
+use crate::money::Money;
+
+use self::{currency::Currency, markdown::Prose, period::Period, quantity::Packaging, slang::Slang, text_match::TextMatch};
+
+pub mod confidence;
+pub mod currency;
+pub mod markdown;
+pub mod period;
+pub mod quantity;
+pub mod slang;
+pub mod text_match;
 
 pub struct Parser {
     main_regex: Regex,
     value_regex: Regex,
     unit_regex: Regex,
+    currency_regex: Regex,
+    range_regex: Regex,
+    prefix_regex: Regex,
+    quantity_regex: Regex,
+    period_regex: Regex,
+    slang: Slang,
 }
 
 pub enum ParseResult {
     // parsed some cash value
-    Value(String, f32),
+    Value(TextMatch, Money),
+    // parsed cash value in foreign currency, converted to CZK by the bot
+    ForeignValue(TextMatch, Money, Currency),
+    // parsed range of cash values - from, to
+    Range(TextMatch, Money, Money),
+    // quantity of Braník the author asks the price of - count and packaging
+    Quantity(TextMatch, f64, Packaging),
+    // any of the cash values above paid periodically - i.e. "45k měsíčně"
+    Periodic(Box<ParseResult>, Period),
     // no cash value, keyword detected
-    Keyword
+    Keyword(TextMatch)
 }
 
 impl PartialEq for ParseResult {
     fn eq(&self, other: &Self) -> bool {
+        // texts don't need to match, we only care about value in the same sentence
+        if self.text_match().sentence_span != other.text_match().sentence_span {
+            return false;
+        }
+        match (self, other) {
+            (ParseResult::Value(_, value), ParseResult::Value(_, other_value)) => other_value == value,
+            (ParseResult::ForeignValue(_, value, currency), ParseResult::ForeignValue(_, other_value, other_currency)) =>
+                other_value == value && other_currency == currency,
+            (ParseResult::Range(_, from, to), ParseResult::Range(_, other_from, other_to)) => other_from == from && other_to == to,
+            (ParseResult::Quantity(_, count, packaging), ParseResult::Quantity(_, other_count, other_packaging)) =>
+                other_count == count && other_packaging == packaging,
+            (ParseResult::Periodic(result, period), ParseResult::Periodic(other_result, other_period)) =>
+                other_result == result && other_period == period,
+            (ParseResult::Keyword(_), ParseResult::Keyword(_)) => true,
+            _ => false
+        }
+    }
+}
+
+impl ParseResult {
+    pub fn text_match(&self) -> &TextMatch {
         match self {
-            ParseResult::Value(_, value) => {
-                match other {
-                    ParseResult::Keyword => false,
-                    // texts don't need to match, we only care about value
-                    ParseResult::Value(_, other_value) => other_value == value
-                }
-            }
-            ParseResult::Keyword => {
-                match other {
-                    ParseResult::Keyword => true,
-                    ParseResult::Value(_, _) => false
-                }
-            }
+            ParseResult::Value(text_match, _)
+                | ParseResult::ForeignValue(text_match, _, _)
+                | ParseResult::Range(text_match, _, _)
+                | ParseResult::Quantity(text_match, _, _)
+                | ParseResult::Keyword(text_match) => text_match,
+            ParseResult::Periodic(result, _) => result.text_match()
         }
     }
+
+    fn text_match_mut(&mut self) -> &mut TextMatch {
+        match self {
+            ParseResult::Value(text_match, _)
+                | ParseResult::ForeignValue(text_match, _, _)
+                | ParseResult::Range(text_match, _, _)
+                | ParseResult::Quantity(text_match, _, _)
+                | ParseResult::Keyword(text_match) => text_match,
+            ParseResult::Periodic(result, _) => result.text_match_mut()
+        }
+    }
+}
+
+// one line description for the command line - "Value 500,00 Kč '500 kc' (confidence 1.00)"
+impl fmt::Display for ParseResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseResult::Value(_, value) => write!(f, "Value {} Kč", value)?,
+            ParseResult::ForeignValue(_, value, currency) => write!(f, "ForeignValue {} {}", value, currency.code())?,
+            ParseResult::Range(_, from, to) => write!(f, "Range {} - {} Kč", from, to)?,
+            ParseResult::Quantity(_, count, packaging) => write!(f, "Quantity {} {:?}", count, packaging)?,
+            ParseResult::Periodic(result, period) => return write!(f, "{:?} {}", period, result),
+            ParseResult::Keyword(_) => write!(f, "Keyword")?
+        }
+        let text_match = self.text_match();
+        write!(f, " '{}' (confidence {:.2})", text_match.text, text_match.confidence)
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Parser {
     pub fn new() -> Parser {
-        let parser = Parser {
+        Parser {
             main_regex: Regex::new(r"( |^)(((\d+[ ,.]?)+?(kc|kč|czk|mega|korun))|(\d+[,.]?\d+(k))|(\d+[k]))+(\b)|((\d+[ .|,]?)+(,-))").unwrap(),
             value_regex: Regex::new(r"(\d?[ ,.]?)+(\d+)").unwrap(),
-            unit_regex: Regex::new(r"([\p{L}+]+)|(mega)|(,-)").unwrap()
-        };
-        parser
+            unit_regex: Regex::new(r"([\p{L}+]+)|(mega)|(,-)").unwrap(),
+            currency_regex: Regex::new(r"(?P<pre>€|\$|£|eur|usd|gbp|pln) ?(?P<pre_value>\d+(?:[ .,]\d+)*)(?P<pre_k>k)?|(?P<value>\d+(?:[ .,]\d+)*)(?P<k>k)? ?(?P<suf>€|\$|£|zł|zl|eura|euro|eur|usd|gbp|pln|dolar\p{L}*|libr\p{L}*|liber|zlot\p{L}*|złot\p{L}*)").unwrap(),
+            range_regex: Regex::new(r"(?:od )?(?P<from>\d+(?:[.,]\d+)*)(?P<from_k>k)? ?(?:-|–|až|do) ?(?P<to>\d+(?:[.,]\d+)*) ?(?P<unit>kč|kc|czk|korun\p{L}*|,-|k|tisíc\p{L}*|tis\.?|mega|milion\p{L}*|mil\.?)").unwrap(),
+            prefix_regex: Regex::new(r"(?:kč|kc|czk) ?(?P<value>\d+(?:[ .]\d{3})*(?:,\d{1,2})?)(?:,-)?").unwrap(),
+            quantity_regex: Regex::new(r"(?:(?P<count>\d+(?:[,.]\d+)?) ?|(?P<word>\p{L}+) )?(?:(?P<brand_unit>br[aá]n[ií][kčc]\p{L}*|dvoulitrov\p{L}*)|(?P<unit>petk\p{L}*|petek|lahev|lahv\p{L}*|bal[ií]k\p{L}*|balen[ií]|bedn\p{L}*|beden|palet\p{L}*|litr\p{L}*|l) br[aá]n[ií][kčc]\p{L}*)").unwrap(),
+            period_regex: Regex::new(r"^ ?(?:/ ?(?P<slash>hodinu|hodina|hod|h|den|d|týden|tyden|měsíc|mesic|měs|mes|m|rok|r)\.?|(?:za|na) (?P<word>hodinu|den|týden|tyden|měsíc|mesic|rok)|(?P<adverb>měsíčně|mesicne|ročně|rocne|denně|denne|týdně|tydne|hodinově|hodinove))").unwrap(),
+            slang: Slang::new()
+        }
     }
 
     const KEYWORDS: [&'static str; 6] = [
@@ -51,63 +131,329 @@ impl Parser {
         "braníček",
         "bráníček"];
 
+    // parses only author's own text of the reddit comment, spans of the results point to the original body
+    pub fn parse_comment(&self, body: &str) -> Option<Vec<ParseResult>> {
+        let prose = Prose::from_markdown(body);
+        let mut results = self.parse(&prose.text)?;
+        for result in results.iter_mut() {
+            let text_match = result.text_match_mut();
+            text_match.span = prose.original_range(&text_match.span);
+            text_match.sentence_span = prose.original_range(&text_match.sentence_span);
+        }
+        Some(results)
+    }
+
     pub fn parse(&self, text: &str) -> Option<Vec<ParseResult>> {
-        let binding = text.to_lowercase();
-        let is_match = self.main_regex.is_match(&binding);
+        // parsing is done on normalized text, spans are mapped back to the original text at the end
+        let (binding, sources) = Self::normalize(text);
+        let range_results = self.parse_ranges(&binding);
+        let slang_results = self.slang.parse(&binding);
+        let quantity_results = self.parse_quantities(&binding);
+        let is_match = self.main_regex.is_match(&binding) || self.currency_regex.is_match(&binding)
+            || !range_results.is_empty() || !slang_results.is_empty() || !quantity_results.is_empty();
         // no value in the text
         if !is_match {
             // check for keywords
-            match Self::check_for_keyword(&binding) {
-                false => return None,
-                true => return Some(vec![ParseResult::Keyword])
+            return Self::find_keyword(&binding).map(|range| {
+                let text_match = TextMatch::new(binding[range.clone()].to_string(), range);
+                vec![ParseResult::Keyword(Self::to_original(text_match, &sources, text))]
+            });
+        }
+        // earlier results take precedence over later overlapping ones,
+        // i.e. "30 - 50 kč" is a range and not "50 kč", "5k €" is not "5k" in CZK
+        let mut parsed_results: Vec<ParseResult> = range_results;
+        let mut push_result = |result: ParseResult| {
+            let span = &result.text_match().span;
+            if !parsed_results.iter().any(|r| r.text_match().span.start < span.end && span.start < r.text_match().span.end) {
+                parsed_results.push(result);
             }
+        };
+        for result in self.parse_foreign_values(&binding) {
+            push_result(result);
+        }
+        for result in self.parse_prefix_values(&binding) {
+            push_result(result);
+        }
+        for result in quantity_results {
+            push_result(result);
+        }
+        for (range, value) in slang_results {
+            push_result(ParseResult::Value(TextMatch::new(binding[range.clone()].to_string(), range), value));
         }
-        let mut parsed_results: Vec<ParseResult> = vec![];
         let captures = self.main_regex.captures_iter(&binding);
         for cap in captures {
+            let full_match = cap.get(0).unwrap();
             let cap = &cap[0].trim();
-            let value = self.get_value_from_match(cap)?;
-            let value = self.get_true_value(value, cap);
-            if value == 0.0 {
+            // too big values don't fit into money type
+            let Some(value) = self.get_value_from_match(cap) else {
+                continue;
+            };
+            if !value.is_positive() {
                 continue;
             }
-            let result = ParseResult::Value(cap.to_string(), value);
-            if parsed_results.iter().any(|r| r == &result) {
+            // match may start with whitespace
+            let start = full_match.end() - full_match.as_str().trim_start().len();
+            push_result(ParseResult::Value(TextMatch::new(cap.to_string(), start..full_match.end()), value));
+        }
+        // keep results in the order they appear in the text
+        parsed_results.sort_by_key(|r| r.text_match().span.start);
+        let mut unique_results: Vec<ParseResult> = vec![];
+        for mut result in parsed_results {
+            // "24 braník" is not a bare "k" value
+            let is_quantity = matches!(result, ParseResult::Quantity(..));
+            let text_match = result.text_match_mut();
+            *text_match = text_match.clone().with_sentence(&binding);
+            if !is_quantity {
+                text_match.confidence = confidence::money_confidence(&binding, &text_match.span, &text_match.sentence_span);
+            }
+            let mut result = self.attach_period(&binding, result);
+            let text_match = result.text_match_mut();
+            *text_match = Self::to_original(text_match.clone(), &sources, text);
+            if unique_results.iter().any(|r| r == &result) {
                 continue;
             }
-            parsed_results.push(result);
+            unique_results.push(result);
         }
-        if parsed_results.len() == 0 {
+        if unique_results.is_empty() {
             return None;
         }
-        Some(parsed_results)
+        Some(unique_results)
     }
 
-    fn check_for_keyword(text: &str) -> bool {
-        text.split(" ").any(|word| Self::KEYWORDS.contains(&word))
+    fn find_keyword(text: &str) -> Option<Range<usize>> {
+        let mut start = 0;
+        for word in text.split(' ') {
+            if Self::KEYWORDS.contains(&word) {
+                return Some(start..start + word.len());
+            }
+            start += word.len() + 1;
+        }
+        None
     }
 
-    fn get_value_from_match(&self, match_str: &str) -> Option<f32> {
-        let capture = self.value_regex.captures(&match_str).unwrap();
-        if match_str.ends_with("k") || match_str.ends_with("mega") {
-            // if value doesn't end with exact unit only remove whitespace
-            capture[0].replace(",", ".").replace(" ", "").parse::<f32>().ok()
-        } else if !capture[0].contains(",") {
-            // remove whitespace and "." which are used as whitespace to improve readability but
-            // don't have any real purpose
-            capture[0].replace(" ", "").replace(".", "").parse::<f32>().ok()
+    // lowercase text with any unicode whitespace (no-break, thin or figure space used in "1 299 Kč") replaced
+    // by ascii space, second value contains offset in the original text for every byte of normalized text
+    fn normalize(text: &str) -> (String, Vec<usize>) {
+        let mut normalized = String::with_capacity(text.len());
+        let mut sources = Vec::with_capacity(text.len() + 1);
+        for (offset, c) in text.char_indices() {
+            let start = normalized.len();
+            match c {
+                '\n' => normalized.push(c),
+                c if c.is_whitespace() => normalized.push(' '),
+                c => normalized.extend(c.to_lowercase())
+            }
+            sources.resize(sources.len() + normalized.len() - start, offset);
+        }
+        sources.push(text.len());
+        (normalized, sources)
+    }
+
+    // maps the match in normalized text to the original text
+    fn to_original(text_match: TextMatch, sources: &[usize], text: &str) -> TextMatch {
+        let span = sources[text_match.span.start]..sources[text_match.span.end];
+        TextMatch { span, ..text_match }.with_sentence(text)
+    }
+
+    // wraps the result into periodic one if the value is followed by period qualifier - "350 kč/hod"
+    fn attach_period(&self, text: &str, mut result: ParseResult) -> ParseResult {
+        // "5 braniku denně" is not paid periodically
+        if let ParseResult::Quantity(..) = result {
+            return result;
+        }
+        let text_after = &text[result.text_match().span.end..];
+        let Some(cap) = self.period_regex.captures(text_after) else {
+            return result;
+        };
+        let full_match = cap.get(0).unwrap();
+        if text_after[full_match.end()..].starts_with(|c: char| c.is_alphanumeric()) {
+            return result;
+        }
+        let word = cap.name("slash").or(cap.name("word")).or(cap.name("adverb")).unwrap();
+        let Some(period) = Period::from_word(word.as_str()) else {
+            return result;
+        };
+        result.text_match_mut().extend(full_match.as_str());
+        ParseResult::Periodic(Box::new(result), period)
+    }
+
+    fn parse_ranges(&self, text: &str) -> Vec<ParseResult> {
+        let mut results = vec![];
+        for cap in self.range_regex.captures_iter(text) {
+            let full_match = cap.get(0).unwrap();
+            if !Self::is_separated(text, &full_match.range()) {
+                continue;
+            }
+            let multiplier = match &cap["unit"] {
+                "k" => 1000,
+                "mega" | "mil" | "mil." => 1000000,
+                unit if unit.starts_with("tis") => 1000,
+                unit if unit.starts_with("milion") => 1000000,
+                _ => 1
+            };
+            let from_multiplier = if cap.name("from_k").is_some() { 1000 } else { multiplier };
+            let from = Self::parse_range_number(&cap["from"], from_multiplier);
+            let to = Self::parse_range_number(&cap["to"], multiplier);
+            let (Some(from), Some(to)) = (from, to) else {
+                continue;
+            };
+            // "50 - 30 kč" is not a range
+            if !from.is_positive() || from >= to {
+                continue;
+            }
+            let text_match = TextMatch::new(full_match.as_str().to_string(), full_match.range());
+            results.push(ParseResult::Range(text_match, from, to));
+        }
+        results
+    }
+
+    // same rules as in get_value_from_match - multiplied values (30-50k) use any delimiter as decimal one,
+    // otherwise "." is used only to improve readability and "," is decimal delimiter
+    fn parse_range_number(value: &str, multiplier: i64) -> Option<Money> {
+        if multiplier > 1 {
+            Money::parse_multiplied(&value.replace(',', "."), multiplier)
         } else {
-            // value contains "," which is used as delimiter for decimal values - i.e. 42,50 Kc
-            capture[0].replace(",", ".").parse::<f32>().ok()
+            Self::parse_czk_number(value)
+        }
+    }
+
+    // matched value has to be a separate word - don't match "5 eurovize" or "a5$"
+    fn is_separated(text: &str, range: &Range<usize>) -> bool {
+        !text[..range.start].ends_with(|c: char| c.is_alphanumeric())
+            && !text[range.end..].starts_with(|c: char| c.is_alphanumeric())
+    }
+
+    // "kolik stojí 24 braníků", "za kolik je bedna branika" - only in sentences asking for the price
+    fn parse_quantities(&self, text: &str) -> Vec<ParseResult> {
+        let mut results = vec![];
+        for cap in self.quantity_regex.captures_iter(text) {
+            let full_match = cap.get(0).unwrap();
+            if !Self::is_separated(text, &full_match.range()) {
+                continue;
+            }
+            let unit = cap.name("brand_unit").or(cap.name("unit")).unwrap();
+            let Some(packaging) = Packaging::from_word(unit.as_str()) else {
+                continue;
+            };
+            // preceding word doesn't have to be a number - "je bedna branika" is a single bedna
+            let (count, start) = match (cap.name("count"), cap.name("word")) {
+                (Some(count), _) => (count.as_str().replace(',', ".").parse::<f64>().ok(), full_match.start()),
+                (None, Some(word)) => match self.slang.number(word.as_str()) {
+                    Some(number) => (Some(number), full_match.start()),
+                    None => (None, unit.start())
+                },
+                (None, None) => (None, full_match.start())
+            };
+            // lone "branik" is a keyword, not a quantity
+            if count.is_none() && unit.as_str().starts_with("br") {
+                continue;
+            }
+            let count = count.unwrap_or(1.0);
+            if count <= 0.0 {
+                continue;
+            }
+            let text_match = TextMatch::new(text[start..full_match.end()].to_string(), start..full_match.end()).with_sentence(text);
+            if !Packaging::is_price_question(&text_match.sentence) {
+                continue;
+            }
+            results.push(ParseResult::Quantity(text_match, count, packaging));
+        }
+        results
+    }
+
+    // currency written before the value - "Kč 1 299" or "CZK 5.000,-". The currency right after a number belongs
+    // to that number ("20 kč 2 rohlíky") and the value has to end the clause, "Kc 2 bylo to" is not a price
+    fn parse_prefix_values(&self, text: &str) -> Vec<ParseResult> {
+        let mut results = vec![];
+        for cap in self.prefix_regex.captures_iter(text) {
+            let full_match = cap.get(0).unwrap();
+            if !Self::is_separated(text, &full_match.range()) {
+                continue;
+            }
+            if text[..full_match.start()].trim_end().ends_with(|c: char| c.is_ascii_digit()) {
+                continue;
+            }
+            if text[full_match.end()..].trim_start_matches(' ').starts_with(|c: char| c.is_alphanumeric()) {
+                continue;
+            }
+            let Some(value) = Self::parse_czk_number(&cap["value"]) else {
+                continue;
+            };
+            if !value.is_positive() {
+                continue;
+            }
+            let text_match = TextMatch::new(full_match.as_str().to_string(), full_match.range());
+            results.push(ParseResult::Value(text_match, value));
         }
+        results
+    }
+
+    // "." and " " are used as thousands separators, "," is decimal delimiter - i.e. 1.299,90
+    fn parse_czk_number(value: &str) -> Option<Money> {
+        Money::parse_multiplied(&value.replace([' ', '.'], "").replace(',', "."), 1)
     }
 
-    fn get_true_value(&self, value: f32, match_str: &str) -> f32 {
+    fn parse_foreign_values(&self, text: &str) -> Vec<ParseResult> {
+        let mut results = vec![];
+        for cap in self.currency_regex.captures_iter(text) {
+            let full_match = cap.get(0).unwrap();
+            if !Self::is_separated(text, &full_match.range()) {
+                continue;
+            }
+            let Some((value, currency)) = Self::get_foreign_value_from_captures(&cap) else {
+                continue;
+            };
+            if !value.is_positive() {
+                continue;
+            }
+            let text_match = TextMatch::new(full_match.as_str().to_string(), full_match.range());
+            results.push(ParseResult::ForeignValue(text_match, value, currency));
+        }
+        results
+    }
+
+    fn get_foreign_value_from_captures(cap: &Captures) -> Option<(Money, Currency)> {
+        let (currency, value, thousands) = match cap.name("pre") {
+            Some(pre) => (pre, &cap["pre_value"], cap.name("pre_k").is_some()),
+            None => (cap.name("suf")?, &cap["value"], cap.name("k").is_some())
+        };
+        let currency = Currency::from_token(currency.as_str())?;
+        let multiplier = if thousands { 1000 } else { 1 };
+        Some((Self::parse_foreign_number(value, multiplier)?, currency))
+    }
+
+    // foreign amounts commonly use both "." and "," as decimal delimiter - i.e. $19.99 or 4,50 €,
+    // so the last delimiter followed by at most two digits is treated as decimal one
+    fn parse_foreign_number(value: &str, multiplier: i64) -> Option<Money> {
+        let value = value.replace(' ', "");
+        match value.rfind([',', '.']) {
+            Some(index) if value.len() - index - 1 <= 2 => {
+                let whole = value[..index].replace([',', '.'], "");
+                Money::parse_multiplied(&format!("{}.{}", whole, &value[index + 1..]), multiplier)
+            },
+            _ => Money::parse_multiplied(&value.replace([',', '.'], ""), multiplier)
+        }
+    }
+
+    fn get_value_from_match(&self, match_str: &str) -> Option<Money> {
+        let capture = self.value_regex.captures(match_str).unwrap();
+        if match_str.ends_with('k') || match_str.ends_with("mega") {
+            // if value doesn't end with exact unit only remove whitespace,
+            // unit is applied before rounding to haléře
+            Money::parse_multiplied(&capture[0].replace(',', ".").replace(' ', ""), self.get_multiplier(match_str))
+        } else {
+            // remove whitespace and "." which are used as whitespace to improve readability but
+            // don't have any real purpose, "," is used as delimiter for decimal values - i.e. 1 299,90 Kc
+            Self::parse_czk_number(&capture[0])
+        }
+    }
+
+    fn get_multiplier(&self, match_str: &str) -> i64 {
         let capture = self.unit_regex.captures(match_str).unwrap();
         match &capture[0] {
-            "k" => return value * 1000.0,
-            "mega" => return value * 1000000.0,
-            _ => return value,
+            "k" => 1000,
+            "mega" => 1000000,
+            _ => 1,
         }
     }
 }
@@ -126,10 +472,10 @@ is just loose numbers like 69420 without any currency specification, same with l
         assert_eq!(results.len(), 11);
         let test_data = "Some text without any value or keyword";
         let results = test_parser.parse(test_data);
-        assert_eq!(true, results.is_none());
+        assert!(results.is_none());
         let test_data = "Some text without any value, but containing branicek keyword";
         let results = test_parser.parse(test_data);
-        assert_eq!(true, results.is_some());
+        assert!(results.is_some());
         let results = results.unwrap();
         assert_eq!(1, results.len());
     }
@@ -142,116 +488,373 @@ but should be implemented 3 000 kc and this 1.900,- is parsed, 3.000.000 kc, 6 0
         let results = test_parser.parse(test_data).unwrap();
         assert_eq!(results.len(), 14);
         let ParseResult::Value(str, value) = &results[0] else {panic!()};
-        assert_eq!("200k", str);
-        assert_eq!(200000.0, *value);
+        assert_eq!("200k", str.text);
+        assert_eq!(Money::from_units(200000), *value);
         let ParseResult::Value(str, value) = &results[1] else {panic!()};
-        assert_eq!("1.5k", str);
-        assert_eq!(1500.0, *value);
+        assert_eq!("1.5k", str.text);
+        assert_eq!(Money::from_units(1500), *value);
         let ParseResult::Value(str, value) = &results[2] else {panic!()};
-        assert_eq!("6,9k", str);
-        assert_eq!(6900.0, *value);
+        assert_eq!("6,9k", str.text);
+        assert_eq!(Money::from_units(6900), *value);
         let ParseResult::Value(str, value) = &results[3] else {panic!()};
-        assert_eq!("2 mega", str);
-        assert_eq!(2000000.0, *value);
+        assert_eq!("2 mega", str.text);
+        assert_eq!(Money::from_units(2000000), *value);
         let ParseResult::Value(str, value) = &results[4] else {panic!()};
-        assert_eq!("60 kc", str);
-        assert_eq!(60.0, *value);
+        assert_eq!("60 kc", str.text);
+        assert_eq!(Money::from_units(60), *value);
         let ParseResult::Value(str, value) = &results[5] else {panic!()};
-        assert_eq!("100kc", str);
-        assert_eq!(100.0, *value);
+        assert_eq!("100kc", str.text);
+        assert_eq!(Money::from_units(100), *value);
         let ParseResult::Value(str, value) = &results[6] else {panic!()};
-        assert_eq!("3 000 kc", str);
-        assert_eq!(3000.0, *value);
+        assert_eq!("3 000 kc", str.text);
+        assert_eq!(Money::from_units(3000), *value);
         let ParseResult::Value(str, value) = &results[7] else {panic!()};
-        assert_eq!("1.900,-", str);
-        assert_eq!(1900.0, *value);
+        assert_eq!("1.900,-", str.text);
+        assert_eq!(Money::from_units(1900), *value);
         let ParseResult::Value(str, value) = &results[8] else {panic!()};
-        assert_eq!("3.000.000 kc", str);
-        assert_eq!(3000000.0, *value);
+        assert_eq!("3.000.000 kc", str.text);
+        assert_eq!(Money::from_units(3000000), *value);
         let ParseResult::Value(str, value) = &results[9] else {panic!()};
-        assert_eq!("6 000 000 czk", str);
-        assert_eq!(6000000.0, *value);
+        assert_eq!("6 000 000 czk", str.text);
+        assert_eq!(Money::from_units(6000000), *value);
         let ParseResult::Value(str, value) = &results[10] else {panic!()};
-        assert_eq!("3.5 mega", str);
-        assert_eq!(3500000.0, *value);
-        let ParseResult::Value(str, value) = &results[11] else {panic!()};
-        assert_eq!("50 kč", str);
-        assert_eq!(50.0, *value);
+        assert_eq!("3.5 mega", str.text);
+        assert_eq!(Money::from_units(3500000), *value);
+        let ParseResult::Range(str, from, to) = &results[11] else {panic!()};
+        assert_eq!("30 - 50 kč", str.text);
+        assert_eq!(Money::from_units(30), *from);
+        assert_eq!(Money::from_units(50), *to);
         let ParseResult::Value(str, value) = &results[12] else {panic!()};
-        assert_eq!("42,50 kc", str);
-        assert_eq!(42.5, *value);
+        assert_eq!("42,50 kc", str.text);
+        assert_eq!(Money::from_halere(4250), *value);
         let ParseResult::Value(str, value) = &results[13] else {panic!()};
-        assert_eq!("0,90kc", str);
-        assert_eq!(0.9, *value);
+        assert_eq!("0,90kc", str.text);
+        assert_eq!(Money::from_halere(90), *value);
+    }
+
+    #[test]
+    fn test_parse_foreign_value() {
+        let test_parser = Parser::new();
+        let test_data = "Za 500 € bych to nebral, v UK stoji £20 a v US $19.99, v Polsku 45,50 zł, taky 100 eur, 5k € nebo 30 dolarů.
+Tohle ale neni cena: 5 eurovize, eurofondy 2023";
+        let results = test_parser.parse(test_data).unwrap();
+        assert_eq!(results.len(), 7);
+        let ParseResult::ForeignValue(str, value, currency) = &results[0] else {panic!()};
+        assert_eq!("500 €", str.text);
+        assert_eq!(Money::from_units(500), *value);
+        assert_eq!(Currency::Eur, *currency);
+        let ParseResult::ForeignValue(str, value, currency) = &results[1] else {panic!()};
+        assert_eq!("£20", str.text);
+        assert_eq!(Money::from_units(20), *value);
+        assert_eq!(Currency::Gbp, *currency);
+        let ParseResult::ForeignValue(str, value, currency) = &results[2] else {panic!()};
+        assert_eq!("$19.99", str.text);
+        assert_eq!(Money::from_halere(1999), *value);
+        assert_eq!(Currency::Usd, *currency);
+        let ParseResult::ForeignValue(str, value, currency) = &results[3] else {panic!()};
+        assert_eq!("45,50 zł", str.text);
+        assert_eq!(Money::from_halere(4550), *value);
+        assert_eq!(Currency::Pln, *currency);
+        let ParseResult::ForeignValue(str, value, currency) = &results[4] else {panic!()};
+        assert_eq!("100 eur", str.text);
+        assert_eq!(Money::from_units(100), *value);
+        assert_eq!(Currency::Eur, *currency);
+        let ParseResult::ForeignValue(str, value, currency) = &results[5] else {panic!()};
+        assert_eq!("5k €", str.text);
+        assert_eq!(Money::from_units(5000), *value);
+        assert_eq!(Currency::Eur, *currency);
+        let ParseResult::ForeignValue(str, value, currency) = &results[6] else {panic!()};
+        assert_eq!("30 dolarů", str.text);
+        assert_eq!(Money::from_units(30), *value);
+        assert_eq!(Currency::Usd, *currency);
+    }
+
+    #[test]
+    fn test_parse_slang_value() {
+        let test_parser = Parser::new();
+        let test_data = "Dal bych za to tak litr, spis pětikilo, dvě stovky nebo dvě stě korun. Barak stal 5 melounů, 1,5 milionu nebo půl mega, stat dluzi 3 mld";
+        let results = test_parser.parse(test_data).unwrap();
+        assert_eq!(results.len(), 7);
+        let ParseResult::Value(str, value) = &results[0] else {panic!()};
+        assert_eq!("litr", str.text);
+        assert_eq!(Money::from_units(1000), *value);
+        let ParseResult::Value(str, value) = &results[1] else {panic!()};
+        assert_eq!("pětikilo", str.text);
+        assert_eq!(Money::from_units(500), *value);
+        // "dvě stě korun" is the same value as "dvě stovky"
+        let ParseResult::Value(str, value) = &results[2] else {panic!()};
+        assert_eq!("dvě stovky", str.text);
+        assert_eq!(Money::from_units(200), *value);
+        let ParseResult::Value(str, value) = &results[3] else {panic!()};
+        assert_eq!("5 melounů", str.text);
+        assert_eq!(Money::from_units(5000000), *value);
+        let ParseResult::Value(str, value) = &results[4] else {panic!()};
+        assert_eq!("1,5 milionu", str.text);
+        assert_eq!(Money::from_units(1500000), *value);
+        let ParseResult::Value(str, value) = &results[5] else {panic!()};
+        assert_eq!("půl mega", str.text);
+        assert_eq!(Money::from_units(500000), *value);
+        let ParseResult::Value(str, value) = &results[6] else {panic!()};
+        assert_eq!("3 mld", str.text);
+        assert_eq!(Money::from_units(3000000000), *value);
+    }
+
+    #[test]
+    fn test_parse_range_value() {
+        let test_parser = Parser::new();
+        let test_data = "Stoji to 30 - 50 kč, nekde 30-50k, jinde od 30 do 50 tisíc, ale taky 1.000-1.500,- nebo 1,5-2 mega.
+Rozsah 9-5 bez meny neni castka a 50 - 30 kč neni rozsah";
+        let results = test_parser.parse(test_data).unwrap();
+        // "od 30 do 50 tisíc" is the same range as "30-50k"
+        assert_eq!(results.len(), 5);
+        let ParseResult::Range(str, from, to) = &results[0] else {panic!()};
+        assert_eq!("30 - 50 kč", str.text);
+        assert_eq!((Money::from_units(30), Money::from_units(50)), (*from, *to));
+        let ParseResult::Range(str, from, to) = &results[1] else {panic!()};
+        assert_eq!("30-50k", str.text);
+        assert_eq!((Money::from_units(30000), Money::from_units(50000)), (*from, *to));
+        let ParseResult::Range(str, from, to) = &results[2] else {panic!()};
+        assert_eq!("1.000-1.500,-", str.text);
+        assert_eq!((Money::from_units(1000), Money::from_units(1500)), (*from, *to));
+        let ParseResult::Range(str, from, to) = &results[3] else {panic!()};
+        assert_eq!("1,5-2 mega", str.text);
+        assert_eq!((Money::from_units(1500000), Money::from_units(2000000)), (*from, *to));
+        let ParseResult::Value(str, value) = &results[4] else {panic!()};
+        assert_eq!("30 kč", str.text);
+        assert_eq!(Money::from_units(30), *value);
+        let results = test_parser.parse("Plat od 30 do 50 tisíc").unwrap();
+        let ParseResult::Range(str, from, to) = &results[0] else {panic!()};
+        assert_eq!("od 30 do 50 tisíc", str.text);
+        assert_eq!((Money::from_units(30000), Money::from_units(50000)), (*from, *to));
+    }
+
+    #[test]
+    fn test_parse_periodic_value() {
+        let test_parser = Parser::new();
+        let test_data = "Beru 45k měsíčně, brigada za 350 Kč/hod, sef ma 600k ročně a najem 20-25k za měsíc. Obcas 500 kč, ale 45k/měs neni 45k za minutu";
+        let results = test_parser.parse(test_data).unwrap();
+        assert_eq!(results.len(), 7);
+        let ParseResult::Periodic(result, period) = &results[0] else {panic!()};
+        let ParseResult::Value(str, value) = result.as_ref() else {panic!()};
+        assert_eq!("45k měsíčně", str.text);
+        assert_eq!(Money::from_units(45000), *value);
+        assert_eq!(Period::Month, *period);
+        let ParseResult::Periodic(result, period) = &results[1] else {panic!()};
+        let ParseResult::Value(str, value) = result.as_ref() else {panic!()};
+        assert_eq!("350 kč/hod", str.text);
+        assert_eq!(Money::from_units(350), *value);
+        assert_eq!(Period::Hour, *period);
+        let ParseResult::Periodic(result, period) = &results[2] else {panic!()};
+        let ParseResult::Value(str, value) = result.as_ref() else {panic!()};
+        assert_eq!("600k ročně", str.text);
+        assert_eq!(Money::from_units(600000), *value);
+        assert_eq!(Period::Year, *period);
+        let ParseResult::Periodic(result, period) = &results[3] else {panic!()};
+        let ParseResult::Range(str, from, to) = result.as_ref() else {panic!()};
+        assert_eq!("20-25k za měsíc", str.text);
+        assert_eq!((Money::from_units(20000), Money::from_units(25000)), (*from, *to));
+        assert_eq!(Period::Month, *period);
+        let ParseResult::Value(str, value) = &results[4] else {panic!()};
+        assert_eq!("500 kč", str.text);
+        assert_eq!(Money::from_units(500), *value);
+        // same value as "45k měsíčně", but in another sentence
+        let ParseResult::Periodic(result, period) = &results[5] else {panic!()};
+        let ParseResult::Value(str, value) = result.as_ref() else {panic!()};
+        assert_eq!("45k/měs", str.text);
+        assert_eq!(Money::from_units(45000), *value);
+        assert_eq!(Period::Month, *period);
+        let ParseResult::Value(str, value) = &results[6] else {panic!()};
+        assert_eq!("45k", str.text);
+        assert_eq!(Money::from_units(45000), *value);
+    }
+
+    #[test]
+    fn test_parse_confidence() {
+        let test_parser = Parser::new();
+        let test_data = "Monitor 4k stál 12k, uběhl jsem 10k. Za 5k bych to bral a 600 kč taky";
+        let results = test_parser.parse(test_data).unwrap();
+        assert_eq!(results.len(), 5);
+        let confidences: Vec<f32> = results.iter().map(|r| r.text_match().confidence).collect();
+        // "4k" next to monitor, "12k" next to both monitor and price word
+        assert!(confidences[0] < 0.5);
+        assert!(confidences[1] < confidence::CERTAIN);
+        assert!(confidences[2] < 0.5);
+        assert!(confidences[3] > 0.5);
+        assert_eq!(confidence::CERTAIN, confidences[4]);
+    }
+
+    #[test]
+    fn test_parse_comment() {
+        let test_parser = Parser::new();
+        let body = "&gt; Koupil jsem to za 500 kc\n\nTo je hodne, [tady](https://shop.cz/produkt-600kc/) to maji za **300 Kč** a `700kc` je chyba";
+        let results = test_parser.parse_comment(body).unwrap();
+        assert_eq!(results.len(), 1);
+        let ParseResult::Value(str, value) = &results[0] else {panic!()};
+        assert_eq!("300 kč", str.text);
+        assert_eq!(Money::from_units(300), *value);
+        assert_eq!("300 Kč", &body[str.span.clone()]);
+        assert_eq!("To je hodne, tady to maji za 300 Kč a  je chyba", str.sentence);
+        assert_eq!("To je hodne, [tady](https://shop.cz/produkt-600kc/) to maji za **300 Kč** a `700kc` je chyba", &body[str.sentence_span.clone()]);
+        let body = "> 500 kc\n\nTo je jako 20 Braníků\n\n^(Jsem bot, 500 kc je 12 dvoulitrovek)";
+        assert!(test_parser.parse_comment(body).is_none());
+    }
+
+    #[test]
+    fn test_parse_foreign_number() {
+        assert_eq!(Some(Money::from_halere(1999)), Parser::parse_foreign_number("19.99", 1));
+        assert_eq!(Some(Money::from_halere(450)), Parser::parse_foreign_number("4,5", 1));
+        assert_eq!(Some(Money::from_units(1500)), Parser::parse_foreign_number("1,500", 1));
+        assert_eq!(Some(Money::from_units(1500)), Parser::parse_foreign_number("1 500", 1));
+        assert_eq!(Some(Money::from_halere(123456)), Parser::parse_foreign_number("1.234,56", 1));
+        assert_eq!(Some(Money::from_units(4500)), Parser::parse_foreign_number("4,5", 1000));
     }
 
     #[test]
-    fn test_get_true_value(){
+    fn test_get_value_from_match() {
         let test_parser = Parser::new();
-        let value = 100.0;
-        let match_str = "100kc";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_eq!(value, true_value);
-        let value = 399.0;
-        let match_str = "399 kč";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_eq!(value, true_value);
-        let value = 1.5;
-        let match_str = "1,5k";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_ne!(value, true_value);
-        assert_eq!(value * 1000.0, true_value);
-        let value = 5.0;
-        let match_str = "5 mega";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_ne!(value, true_value);
-        assert_eq!(value * 1000000.0, true_value);
-        let value = 3000.0;
-        let match_str = "3 000 kc";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_eq!(value, true_value);
-        let value = 1900.0;
-        let match_str = "1.900,-";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_eq!(value, true_value);
-        let value = 3000000.0;
-        let match_str = "3.000.000 kc";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_eq!(value, true_value);
-        let value = 6000000.0;
-        let match_str = "6 000 000 czk";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_eq!(value, true_value);
-        let value = 69420.0;
-        let match_str = "69.420kc";
-        let true_value = test_parser.get_true_value(value, match_str);
-        assert_eq!(value, true_value);
+        assert_eq!(1, test_parser.get_multiplier("100kc"));
+        assert_eq!(1000, test_parser.get_multiplier("1,5k"));
+        assert_eq!(1000000, test_parser.get_multiplier("5 mega"));
+        assert_eq!(1, test_parser.get_multiplier("1.900,-"));
+        assert_eq!(Some(Money::from_units(100)), test_parser.get_value_from_match("100kc"));
+        assert_eq!(Some(Money::from_units(399)), test_parser.get_value_from_match("399 kč"));
+        assert_eq!(Some(Money::from_units(1500)), test_parser.get_value_from_match("1,5k"));
+        assert_eq!(Some(Money::from_halere(123450)), test_parser.get_value_from_match("1.2345k"));
+        assert_eq!(Some(Money::from_units(5000000)), test_parser.get_value_from_match("5 mega"));
+        assert_eq!(Some(Money::from_units(3000)), test_parser.get_value_from_match("3 000 kc"));
+        assert_eq!(Some(Money::from_units(1900)), test_parser.get_value_from_match("1.900,-"));
+        assert_eq!(Some(Money::from_units(3000000)), test_parser.get_value_from_match("3.000.000 kc"));
+        assert_eq!(Some(Money::from_units(6000000)), test_parser.get_value_from_match("6 000 000 czk"));
+        assert_eq!(Some(Money::from_units(69420)), test_parser.get_value_from_match("69.420kc"));
+        assert_eq!(Some(Money::from_halere(129990)), test_parser.get_value_from_match("1 299,90 kč"));
+        // absurd amounts don't fit, they are skipped instead of overflowing
+        assert_eq!(None, test_parser.get_value_from_match("99999999999999999999 kc"));
     }
 
     #[test]
     fn check_text_result() {
         let text = "sample text without any keyword";
-        let result = Parser::check_for_keyword(text);
-        assert_eq!(false, result);
+        let result = Parser::find_keyword(text).is_some();
+        assert!(!result);
         let text = "sample text with branik in it";
-        let result = Parser::check_for_keyword(text);
-        assert_eq!(true, result);
+        let result = Parser::find_keyword(text).is_some();
+        assert!(result);
+    }
+
+    fn text_match(text: &str, matched: &str) -> TextMatch {
+        let start = text.find(matched).unwrap();
+        TextMatch::new(matched.to_string(), start..start + matched.len()).with_sentence(text)
     }
 
     #[test]
     fn test_parse_result_compare() {
-        let result1 = ParseResult::Keyword;
-        let result2 = ParseResult::Keyword;
-        assert_eq!(true, result2 == result1);
-        assert_eq!(true, result1 == result2);
-        let result1 = ParseResult::Value("100kc".to_string(), 100.0);
-        assert_eq!(false, result1 == result2);
-        assert_eq!(false, result2 == result1);
-        let result2 = ParseResult::Value("3 000 kc".to_string(), 3000.0);
-        assert_eq!(false, result1 == result2);
-        assert_eq!(false, result2 == result1);
-        let result2 = ParseResult::Value("100,-".to_string(), 100.0);
-        assert_eq!(true, result2 == result1);
-        assert_eq!(true, result1 == result2);
+        let text = "Muj branik za 100kc, 3 000 kc nebo 100,-, taky 100 € nebo €100 a $100. Jinde 100 kc";
+        let result1 = ParseResult::Keyword(text_match(text, "branik"));
+        let result2 = ParseResult::Keyword(text_match(text, "branik"));
+        assert!(result2 == result1);
+        assert!(result1 == result2);
+        let result1 = ParseResult::Value(text_match(text, "100kc"), Money::from_units(100));
+        assert!(result1 != result2);
+        assert!(result2 != result1);
+        let result2 = ParseResult::Value(text_match(text, "3 000 kc"), Money::from_units(3000));
+        assert!(result1 != result2);
+        assert!(result2 != result1);
+        // same value in the same sentence
+        let result2 = ParseResult::Value(text_match(text, "100,-"), Money::from_units(100));
+        assert!(result2 == result1);
+        assert!(result1 == result2);
+        // same value in another sentence
+        let result2 = ParseResult::Value(text_match(text, "100 kc"), Money::from_units(100));
+        assert!(result2 != result1);
+        assert!(result1 != result2);
+        let result2 = ParseResult::ForeignValue(text_match(text, "100 €"), Money::from_units(100), Currency::Eur);
+        assert!(result1 != result2);
+        let result1 = ParseResult::ForeignValue(text_match(text, "€100"), Money::from_units(100), Currency::Eur);
+        assert!(result1 == result2);
+        let result1 = ParseResult::ForeignValue(text_match(text, "$100"), Money::from_units(100), Currency::Usd);
+        assert!(result1 != result2);
+    }
+
+    #[test]
+    fn test_parse_span_and_sentence() {
+        let test_parser = Parser::new();
+        let test_data = "Nevim. Za 500 Kč bych to bral! A co 3.000 kc?";
+        let results = test_parser.parse(test_data).unwrap();
+        let ParseResult::Value(str, _) = &results[0] else {panic!()};
+        assert_eq!("500 Kč", &test_data[str.span.clone()]);
+        assert_eq!("Za 500 Kč bych to bral!", str.sentence);
+        assert_eq!("Za 500 Kč bych to bral!", str.quote());
+        let ParseResult::Value(str, _) = &results[1] else {panic!()};
+        assert_eq!("3.000 kc", &test_data[str.span.clone()]);
+        assert_eq!("A co 3.000 kc?", str.sentence);
+    }
+
+    #[test]
+    fn test_parse_prefix_and_unicode_space_value() {
+        let test_parser = Parser::new();
+        let test_data = "Stoji to Kč 1 299, jinde 1\u{a0}199 Kč, v akci 1\u{202f}299,90 Kč a dodani CZK 5.000,-. Nebo kc 80.";
+        let results = test_parser.parse(test_data).unwrap();
+        assert_eq!(results.len(), 5);
+        let ParseResult::Value(str, value) = &results[0] else {panic!()};
+        assert_eq!("kč 1 299", str.text);
+        assert_eq!(Money::from_units(1299), *value);
+        let ParseResult::Value(str, value) = &results[1] else {panic!()};
+        assert_eq!("1\u{a0}199 Kč", &test_data[str.span.clone()]);
+        assert_eq!(Money::from_units(1199), *value);
+        let ParseResult::Value(str, value) = &results[2] else {panic!()};
+        assert_eq!("1\u{202f}299,90 Kč", &test_data[str.span.clone()]);
+        assert_eq!(Money::from_halere(129990), *value);
+        let ParseResult::Value(str, value) = &results[3] else {panic!()};
+        assert_eq!("CZK 5.000,-", &test_data[str.span.clone()]);
+        assert_eq!(Money::from_units(5000), *value);
+        assert_eq!("Stoji to Kč 1 299, jinde 1\u{a0}199 Kč, v akci 1\u{202f}299,90 Kč a dodani CZK 5.000,-.", str.sentence);
+        let ParseResult::Value(str, value) = &results[4] else {panic!()};
+        assert_eq!("kc 80", str.text);
+        assert_eq!(Money::from_units(80), *value);
+    }
+
+    #[test]
+    fn test_parse_prefix_after_value() {
+        let test_parser = Parser::new();
+        // the currency belongs to the number before it
+        let results = test_parser.parse("za 20 kč 2 rohlíky").unwrap();
+        assert_eq!(results.len(), 1);
+        let ParseResult::Value(str, value) = &results[0] else {panic!()};
+        assert_eq!("20 kč", str.text);
+        assert_eq!(Money::from_units(20), *value);
+        // not a price followed by a word
+        let results = test_parser.parse("Kc 2 bylo to 100 kč").unwrap();
+        assert_eq!(results.len(), 1);
+        let ParseResult::Value(str, value) = &results[0] else {panic!()};
+        assert_eq!("100 kč", str.text);
+        assert_eq!(Money::from_units(100), *value);
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        let test_parser = Parser::new();
+        let test_data = "Kolik stojí 24 Braníků? Za kolik je bedna branika a dvě palety braniku? Kolik vyjde 10 dvoulitrovek nebo 5 litrů braníku?
+Vypil jsem 5 braniku. Muj branik";
+        let results = test_parser.parse(test_data).unwrap();
+        assert_eq!(results.len(), 5);
+        let ParseResult::Quantity(str, count, packaging) = &results[0] else {panic!()};
+        assert_eq!("24 Braníků", &test_data[str.span.clone()]);
+        assert_eq!((24.0, Packaging::Pet), (*count, *packaging));
+        assert_eq!(confidence::CERTAIN, str.confidence);
+        let ParseResult::Quantity(str, count, packaging) = &results[1] else {panic!()};
+        assert_eq!("bedna branika", str.text);
+        assert_eq!((1.0, Packaging::Pack), (*count, *packaging));
+        let ParseResult::Quantity(str, count, packaging) = &results[2] else {panic!()};
+        assert_eq!("dvě palety braniku", str.text);
+        assert_eq!((2.0, Packaging::Palett), (*count, *packaging));
+        let ParseResult::Quantity(str, count, packaging) = &results[3] else {panic!()};
+        assert_eq!("10 dvoulitrovek", str.text);
+        assert_eq!((10.0, Packaging::Pet), (*count, *packaging));
+        let ParseResult::Quantity(str, count, packaging) = &results[4] else {panic!()};
+        assert_eq!("5 litrů braníku", str.text);
+        assert_eq!((5.0, Packaging::Liter), (*count, *packaging));
+        // lone keyword is still a keyword
+        let results = test_parser.parse("Kolik stoji branik ted").unwrap();
+        assert!(matches!(results[0], ParseResult::Keyword(_)));
     }
 }
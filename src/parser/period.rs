@@ -0,0 +1,62 @@
+use crate::money::Money;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year
+}
+
+impl Period {
+    // hourly wage is paid for a working day, not for whole 24 hours
+    const WORKING_HOURS_PER_DAY: i64 = 8;
+
+    // maps lowercased qualifier matched by the parser - "/hod", "za měsíc", "ročně"...
+    pub fn from_word(word: &str) -> Option<Period> {
+        let word = word.trim_end_matches('.');
+        match word {
+            "h" | "hod" | "hodinu" | "hodina" | "hodinově" | "hodinove" => Some(Period::Hour),
+            "d" | "den" | "denně" | "denne" => Some(Period::Day),
+            "týden" | "tyden" | "týdně" | "tydne" => Some(Period::Week),
+            "m" | "měs" | "mes" | "měsíc" | "mesic" | "měsíčně" | "mesicne" => Some(Period::Month),
+            "r" | "rok" | "ročně" | "rocne" => Some(Period::Year),
+            _ => None
+        }
+    }
+
+    // value for given period converted to value per single day
+    pub fn to_daily(&self, value: Money) -> Money {
+        match self {
+            Period::Hour => value.mul_ratio(Self::WORKING_HOURS_PER_DAY, 1),
+            Period::Day => value,
+            Period::Week => value.mul_ratio(1, 7),
+            Period::Month => value.mul_ratio(12, 365),
+            Period::Year => value.mul_ratio(1, 365)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_word() {
+        assert_eq!(Some(Period::Hour), Period::from_word("hod."));
+        assert_eq!(Some(Period::Month), Period::from_word("měsíčně"));
+        assert_eq!(Some(Period::Month), Period::from_word("měs"));
+        assert_eq!(Some(Period::Year), Period::from_word("rok"));
+        assert_eq!(Some(Period::Day), Period::from_word("denně"));
+        assert_eq!(None, Period::from_word("minutu"));
+    }
+
+    #[test]
+    fn test_to_daily() {
+        assert_eq!(Money::from_units(2800), Period::Hour.to_daily(Money::from_units(350)));
+        assert_eq!(Money::from_units(100), Period::Week.to_daily(Money::from_units(700)));
+        assert_eq!(Money::from_units(1200), Period::Month.to_daily(Money::from_units(36500)));
+        assert_eq!(Money::from_units(1000), Period::Year.to_daily(Money::from_units(365000)));
+    }
+}
@@ -0,0 +1,58 @@
+// Braník quantity the author asks the price of - "kolik stojí 24 Braníků", "za kolik je bedna branika"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packaging {
+    // single 2l PET bottle
+    Pet,
+    // shrink wrapped pack of bottles, people call it "bedna" as well
+    Pack,
+    Palett,
+    Liter
+}
+
+impl Packaging {
+    // words asking for the price, quantity in any other sentence is just mentioned - "vypil jsem 5 braniku"
+    const PRICE_QUESTION_PREFIXES: [&'static str; 7] = ["kolik", "cen", "stoj", "stál", "stal", "vyjd", "zaplat"];
+
+    // maps lowercased unit matched by the parser - "dvoulitrovek", "bednu", "palety"...
+    pub fn from_word(word: &str) -> Option<Packaging> {
+        match word {
+            w if w.starts_with("bran") || w.starts_with("brán") || w.starts_with("dvoulitrov")
+                || w.starts_with("petk") || w == "petek" || w.starts_with("lahv") || w == "lahev" => Some(Packaging::Pet),
+            w if w.starts_with("balík") || w.starts_with("balik") || w.starts_with("balen") || w.starts_with("bedn") || w == "beden" => Some(Packaging::Pack),
+            w if w.starts_with("palet") => Some(Packaging::Palett),
+            w if w.starts_with("litr") || w == "l" => Some(Packaging::Liter),
+            _ => None
+        }
+    }
+
+    // expects lowercase sentence
+    pub fn is_price_question(sentence: &str) -> bool {
+        sentence.split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+            .any(|w| Self::PRICE_QUESTION_PREFIXES.iter().any(|p| w.starts_with(p)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_word() {
+        assert_eq!(Some(Packaging::Pet), Packaging::from_word("braníků"));
+        assert_eq!(Some(Packaging::Pet), Packaging::from_word("dvoulitrovek"));
+        assert_eq!(Some(Packaging::Pack), Packaging::from_word("bedna"));
+        assert_eq!(Some(Packaging::Pack), Packaging::from_word("balíky"));
+        assert_eq!(Some(Packaging::Palett), Packaging::from_word("paletu"));
+        assert_eq!(Some(Packaging::Liter), Packaging::from_word("litrů"));
+        assert_eq!(None, Packaging::from_word("piv"));
+    }
+
+    #[test]
+    fn test_is_price_question() {
+        assert!(Packaging::is_price_question("kolik stojí 24 braníků?"));
+        assert!(Packaging::is_price_question("za kolik je bedna branika"));
+        assert!(Packaging::is_price_question("jaka je cena palety braniku"));
+        assert!(!Packaging::is_price_question("vypil jsem 5 braniku"));
+    }
+}
@@ -0,0 +1,229 @@
+use std::{collections::HashMap, ops::Range};
+
+use regex::Regex;
+
+use crate::money::Money;
+
+#[derive(Clone, Copy)]
+enum SlangWord {
+    // jedna, dvě, dvacet, sto...
+    Number(f64),
+    // "stě", "sta", "set" - multiplies preceding number by hundred (dvě stě, tři sta, pět set)
+    Hundreds,
+    // tisíc, milion, mld - bool tells whether the amount is money even without currency (5 melounů vs 5 tisíc lidí)
+    Multiplier(f64, bool),
+    // slang money nouns - litr, stovka, pětikilo, meloun
+    Noun(f64),
+    Half,
+    Currency
+}
+
+// parses czech number words and money slang, i.e. "dvě stě korun", "půl mega", "litr", "stovku"
+pub struct Slang {
+    word_regex: Regex,
+    dictionary: HashMap<&'static str, SlangWord>
+}
+
+impl Default for Slang {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Slang {
+    const NUMBERS: [(&'static str, f64); 32] = [
+        ("jeden", 1.0), ("jedna", 1.0), ("jedno", 1.0), ("jednu", 1.0),
+        ("dva", 2.0), ("dvě", 2.0), ("tři", 3.0), ("čtyři", 4.0), ("pět", 5.0), ("šest", 6.0),
+        ("sedm", 7.0), ("osm", 8.0), ("devět", 9.0), ("deset", 10.0), ("jedenáct", 11.0),
+        ("dvanáct", 12.0), ("třináct", 13.0), ("čtrnáct", 14.0), ("patnáct", 15.0),
+        ("šestnáct", 16.0), ("sedmnáct", 17.0), ("osmnáct", 18.0), ("devatenáct", 19.0),
+        ("dvacet", 20.0), ("třicet", 30.0), ("čtyřicet", 40.0), ("padesát", 50.0),
+        ("šedesát", 60.0), ("sedmdesát", 70.0), ("osmdesát", 80.0), ("devadesát", 90.0),
+        ("sto", 100.0)
+    ];
+
+    const MULTIPLIERS: [(&'static str, f64, bool); 17] = [
+        ("tisíc", 1000.0, false), ("tisíce", 1000.0, false), ("tisícu", 1000.0, false),
+        ("milion", 1000000.0, true), ("milión", 1000000.0, true), ("milionu", 1000000.0, true),
+        ("miliony", 1000000.0, true), ("milionů", 1000000.0, true), ("mil", 1000000.0, true),
+        ("mega", 1000000.0, true),
+        ("miliarda", 1000000000.0, true), ("miliardu", 1000000000.0, true),
+        ("miliardy", 1000000000.0, true), ("miliard", 1000000000.0, true),
+        ("miliardě", 1000000000.0, true), ("mld", 1000000000.0, true), ("mlrd", 1000000000.0, true)
+    ];
+
+    const NOUNS: [(&'static str, f64); 42] = [
+        ("pade", 50.0),
+        ("stovka", 100.0), ("stovku", 100.0), ("stovky", 100.0), ("stovek", 100.0), ("stovkou", 100.0), ("stovce", 100.0),
+        ("dvoustovka", 200.0), ("dvoustovku", 200.0), ("dvoustovky", 200.0), ("dvoustovek", 200.0),
+        ("pětikilo", 500.0), ("pětikila", 500.0), ("pětikil", 500.0), ("pětikilem", 500.0),
+        ("pětistovka", 500.0), ("pětistovku", 500.0), ("pětistovky", 500.0), ("pětistovek", 500.0),
+        ("litr", 1000.0), ("litru", 1000.0), ("litry", 1000.0), ("litrů", 1000.0), ("litrem", 1000.0), ("litrech", 1000.0),
+        ("tisícovka", 1000.0), ("tisícovku", 1000.0), ("tisícovky", 1000.0), ("tisícovek", 1000.0),
+        ("dvoutisícovka", 2000.0), ("dvoutisícovku", 2000.0), ("dvoutisícovky", 2000.0), ("dvoutisícovek", 2000.0),
+        ("pětitisícovka", 5000.0), ("pětitisícovku", 5000.0), ("pětitisícovky", 5000.0), ("pětitisícovek", 5000.0),
+        ("meloun", 1000000.0), ("melounu", 1000000.0), ("melouny", 1000000.0), ("melounů", 1000000.0), ("melounem", 1000000.0)
+    ];
+
+    const HUNDREDS: [&'static str; 3] = ["stě", "sta", "set"];
+
+    const CURRENCY: [&'static str; 9] = ["korun", "koruny", "korunu", "koruna", "korunou", "korunách", "kč", "kc", "czk"];
+
+    // "2 litry piva" is not 2000 Kč
+    const EXCLUDED_FOLLOWERS: [&'static str; 8] = ["piv", "bran", "vod", "vín", "mlék", "benz", "naft", "kofol"];
+
+    pub fn new() -> Slang {
+        let mut dictionary = HashMap::new();
+        for (word, value) in Self::NUMBERS {
+            dictionary.insert(word, SlangWord::Number(value));
+        }
+        for (word, value, is_money) in Self::MULTIPLIERS {
+            dictionary.insert(word, SlangWord::Multiplier(value, is_money));
+        }
+        for (word, value) in Self::NOUNS {
+            dictionary.insert(word, SlangWord::Noun(value));
+        }
+        for word in Self::HUNDREDS {
+            dictionary.insert(word, SlangWord::Hundreds);
+        }
+        for word in Self::CURRENCY {
+            dictionary.insert(word, SlangWord::Currency);
+        }
+        dictionary.insert("půl", SlangWord::Half);
+        Slang {
+            word_regex: Regex::new(r"\d+(?:[.,]\d+)?|\p{L}+").unwrap(),
+            dictionary
+        }
+    }
+
+    // value of czech number word - "dvě", "dvacet"
+    pub fn number(&self, word: &str) -> Option<f64> {
+        match self.dictionary.get(word) {
+            Some(SlangWord::Number(value)) => Some(*value),
+            _ => None
+        }
+    }
+
+    // expects lowercase text, returns matched phrases with their position and value
+    pub fn parse(&self, text: &str) -> Vec<(Range<usize>, Money)> {
+        let words: Vec<Range<usize>> = self.word_regex.find_iter(text).map(|m| m.range()).collect();
+        let mut results = vec![];
+        let mut index = 0;
+        while index < words.len() {
+            match self.parse_phrase(text, &words[index..]) {
+                Some((word_count, value)) => {
+                    // words are composed in floating point, the amount is rounded to haléře once at the end
+                    if let Some(value) = Money::from_f64(value) {
+                        results.push((words[index].start..words[index + word_count - 1].end, value));
+                    }
+                    index += word_count;
+                },
+                None => index += 1
+            }
+        }
+        results
+    }
+
+    // tries to read a money phrase starting with the first word, returns number of words used and value
+    fn parse_phrase(&self, text: &str, words: &[Range<usize>]) -> Option<(usize, f64)> {
+        let mut total = 0.0;
+        let mut current: Option<f64> = None;
+        let mut has_words = false;
+        let mut is_money = false;
+        let mut count = 0;
+        while count < words.len() {
+            // phrase can't continue over punctuation - "stovku. Dva"
+            if count > 0 && !text[words[count - 1].end..words[count].start].trim().is_empty() {
+                break;
+            }
+            let word = &text[words[count].clone()];
+            if count == 0 && word.starts_with(|c: char| c.is_ascii_digit()) {
+                current = Some(word.replace(',', ".").parse::<f64>().ok()?);
+                count += 1;
+                continue;
+            }
+            match self.dictionary.get(word) {
+                Some(SlangWord::Number(value)) => current = Some(current.unwrap_or(0.0) + value),
+                Some(SlangWord::Hundreds) if current.is_some() => current = current.map(|c| c * 100.0),
+                Some(SlangWord::Half) if count == 0 => current = Some(0.5),
+                Some(SlangWord::Multiplier(value, money)) => {
+                    // multiplier needs an amount, "milion lidí" or "mega dobrý" is not money
+                    let Some(amount) = current else {
+                        break;
+                    };
+                    total += amount * value;
+                    current = None;
+                    is_money |= money;
+                },
+                _ => break
+            }
+            has_words = true;
+            count += 1;
+        }
+        let mut value = total + current.unwrap_or(0.0);
+        if let Some(word) = words.get(count).filter(|w| count == 0 || text[words[count - 1].end..w.start].trim().is_empty()) {
+            match self.dictionary.get(&text[word.clone()]) {
+                Some(SlangWord::Noun(noun_value)) => {
+                    let is_excluded = words.get(count + 1)
+                        .map(|w| &text[w.clone()])
+                        .is_some_and(|w| Self::EXCLUDED_FOLLOWERS.iter().any(|e| w.starts_with(e)));
+                    if is_excluded {
+                        return None;
+                    }
+                    value = if count == 0 { *noun_value } else { value * noun_value };
+                    is_money = true;
+                    has_words = true;
+                    count += 1;
+                },
+                Some(SlangWord::Currency) if count > 0 => {
+                    is_money = true;
+                    count += 1;
+                },
+                _ => ()
+            }
+        }
+        // plain digits with currency are handled by the main parser
+        if !is_money || !has_words || value == 0.0 {
+            return None;
+        }
+        Some((count, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Vec<(String, Money)> {
+        Slang::new().parse(text).into_iter().map(|(range, value)| (text[range].to_string(), value)).collect()
+    }
+
+    #[test]
+    fn test_parse_slang() {
+        let results = parse("dal jsem za to litr, ona pětikilo a on jen stovku. tři stovky, 5 melounů nebo půl mega");
+        assert_eq!(vec![
+            ("litr".to_string(), Money::from_units(1000)),
+            ("pětikilo".to_string(), Money::from_units(500)),
+            ("stovku".to_string(), Money::from_units(100)),
+            ("tři stovky".to_string(), Money::from_units(300)),
+            ("5 melounů".to_string(), Money::from_units(5000000)),
+            ("půl mega".to_string(), Money::from_units(500000))], results);
+    }
+
+    #[test]
+    fn test_parse_number_words() {
+        let results = parse("stálo to dvě stě korun, pak 1,5 milionu a nakonec 3 mld nebo pět set tisíc kč");
+        assert_eq!(vec![
+            ("dvě stě korun".to_string(), Money::from_units(200)),
+            ("1,5 milionu".to_string(), Money::from_units(1500000)),
+            ("3 mld".to_string(), Money::from_units(3000000000)),
+            ("pět set tisíc kč".to_string(), Money::from_units(500000))], results);
+    }
+
+    #[test]
+    fn test_ignore_non_money() {
+        assert!(parse("koupil jsem 2 litry piva a pět tisíc lidí").is_empty());
+        let results = parse("milion lidí, mega dobrý, 500 kč, dva. stovky");
+        assert_eq!(vec![("stovky".to_string(), Money::from_units(100))], results);
+    }
+}
@@ -0,0 +1,103 @@
+use std::ops::Range;
+
+use super::confidence;
+
+// Where the parsed value was found - matched text and the sentence around it with their byte spans
+// in the parsed text (or in the original comment body, the sentence text itself is without markdown).
+#[derive(Debug, Clone)]
+pub struct TextMatch {
+    pub text: String,
+    pub span: Range<usize>,
+    pub sentence: String,
+    pub sentence_span: Range<usize>,
+    // how sure the parser is that the match is money, 0.0 - 1.0
+    pub confidence: f32
+}
+
+impl From<&str> for TextMatch {
+    fn from(text: &str) -> Self {
+        TextMatch::new(text.to_string(), 0..text.len())
+    }
+}
+
+impl TextMatch {
+    // longer sentences are cut to words around the match
+    const MAX_SENTENCE_LENGTH: usize = 120;
+
+    pub fn new(text: String, span: Range<usize>) -> TextMatch {
+        TextMatch { sentence: text.clone(), sentence_span: span.clone(), text, span, confidence: confidence::CERTAIN }
+    }
+
+    // fills the sentence from the text the match was found in
+    pub fn with_sentence(mut self, text: &str) -> TextMatch {
+        let sentence_span = Self::find_sentence(text, &self.span);
+        self.sentence = text[sentence_span.clone()].to_string();
+        self.sentence_span = sentence_span;
+        self
+    }
+
+    // text to quote in the reply
+    pub fn quote(&self) -> &str {
+        match self.sentence.is_empty() {
+            true => &self.text,
+            false => &self.sentence
+        }
+    }
+
+    pub fn extend(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.span.end += text.len();
+    }
+
+    fn find_sentence(text: &str, span: &Range<usize>) -> Range<usize> {
+        let is_sentence_end = |index: usize, c: char| {
+            // "." in "3.000 kč" or "1.5k" doesn't end the sentence
+            c == '\n' || (matches!(c, '.' | '!' | '?') && text[index + 1..].starts_with(|n: char| n.is_whitespace()))
+        };
+        let start = text[..span.start].char_indices().rev()
+            .find(|(i, c)| is_sentence_end(*i, *c))
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(0);
+        let end = text[span.end..].char_indices()
+            .find(|(i, c)| is_sentence_end(span.end + i, *c))
+            .map(|(i, c)| span.end + i + c.len_utf8())
+            .unwrap_or(text.len());
+        let mut start = start + (text[start..span.start].len() - text[start..span.start].trim_start().len());
+        let mut end = span.end + text[span.end..end].trim_end().len();
+        // cut words from both sides of too long sentence
+        while end - start > Self::MAX_SENTENCE_LENGTH && (start < span.start || end > span.end) {
+            if span.start - start > end - span.end {
+                start = text[start..span.start].find(' ').map(|i| start + i + 1).unwrap_or(span.start);
+            } else {
+                end = text[span.end..end].rfind(' ').map(|i| span.end + i).unwrap_or(span.end);
+            }
+        }
+        start..end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(text: &str, matched: &str) -> String {
+        let start = text.find(matched).unwrap();
+        TextMatch::new(matched.to_string(), start..start + matched.len()).with_sentence(text).sentence
+    }
+
+    #[test]
+    fn test_find_sentence() {
+        let text = "Prvni veta. Druha veta za 3.000 kč a 1.5k! Treti veta?\nCtvrta za 20 kc";
+        assert_eq!("Druha veta za 3.000 kč a 1.5k!", sentence(text, "3.000 kč"));
+        assert_eq!("Druha veta za 3.000 kč a 1.5k!", sentence(text, "1.5k"));
+        assert_eq!("Ctvrta za 20 kc", sentence(text, "20 kc"));
+    }
+
+    #[test]
+    fn test_cut_long_sentence() {
+        let text = format!("{} za 20 kc {}", "slovo ".repeat(30), "slovo ".repeat(30));
+        let result = sentence(&text, "20 kc");
+        assert!(result.len() <= TextMatch::MAX_SENTENCE_LENGTH);
+        assert!(result.contains("za 20 kc slovo"));
+    }
+}
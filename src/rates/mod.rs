@@ -0,0 +1,138 @@
+use std::{collections::HashMap, fs};
+
+use chrono::{Local, NaiveDate};
+
+use crate::{money::Money, parser::currency::Currency};
+
+// CZK exchange rates read from Czech National Bank daily rate file ("denní kurz"), i.e.
+//
+// 17.10.2023 #201
+// země|měna|množství|kód|kurz
+// EMU|euro|1|EUR|24,610
+// Japonsko|jen|100|JPY|15,234
+pub struct ExchangeRates {
+    date: Option<NaiveDate>,
+    // CZK for single unit of the currency, "množství" is already applied
+    rates: HashMap<String, f64>,
+    max_age_days: i64
+}
+
+impl ExchangeRates {
+    const HEADER: &'static str = "země|měna|množství|kód|kurz";
+
+    pub fn load(path: &str, max_age_days: i64) -> ExchangeRates {
+        match fs::read_to_string(path) {
+            Err(_) => {
+                println!("Failed to load exchange rates from {}, foreign currencies won't be converted", path);
+                Self::empty(max_age_days)
+            },
+            Ok(text) => Self::read_rates(&text, max_age_days)
+        }
+    }
+
+    fn empty(max_age_days: i64) -> ExchangeRates {
+        ExchangeRates { date: None, rates: HashMap::new(), max_age_days }
+    }
+
+    pub fn read_rates(rates_str: &str, max_age_days: i64) -> ExchangeRates {
+        let mut lines = rates_str.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+        // first line contains date of the rates and number of the rate file in the year - "17.10.2023 #201"
+        let date = lines.next()
+            .and_then(|l| l.split(' ').next())
+            .and_then(|d| NaiveDate::parse_from_str(d, "%d.%m.%Y").ok());
+        let Some(date) = date else {
+            println!("Failed to read date of the exchange rates");
+            return Self::empty(max_age_days);
+        };
+        if lines.next() != Some(Self::HEADER) {
+            println!("Unexpected exchange rates header, expected '{}'", Self::HEADER);
+            return Self::empty(max_age_days);
+        }
+        let mut rates = HashMap::new();
+        for line in lines {
+            let columns: Vec<&str> = line.split('|').collect();
+            let [_, _, amount, code, rate] = columns[..] else {
+                println!("Invalid exchange rate line '{}'", line);
+                continue;
+            };
+            let amount = amount.trim().parse::<f64>();
+            let rate = rate.trim().replace(',', ".").parse::<f64>();
+            match (amount, rate) {
+                (Ok(amount), Ok(rate)) if amount > 0.0 && rate > 0.0 => {
+                    rates.insert(code.trim().to_uppercase(), rate / amount);
+                },
+                _ => println!("Invalid exchange rate line '{}'", line)
+            }
+        }
+        ExchangeRates { date: Some(date), rates, max_age_days }
+    }
+
+    pub fn date(&self) -> Option<NaiveDate> {
+        self.date
+    }
+
+    pub fn is_stale(&self, today: NaiveDate) -> bool {
+        match self.date {
+            None => true,
+            Some(date) => today.signed_duration_since(date).num_days() > self.max_age_days
+        }
+    }
+
+    pub fn to_czk(&self, value: Money, currency: Currency) -> Option<Money> {
+        self.to_czk_at(value, currency, Local::now().date_naive())
+    }
+
+    // never convert with outdated rates, the reply would quote wrong amount
+    pub fn to_czk_at(&self, value: Money, currency: Currency, today: NaiveDate) -> Option<Money> {
+        if self.is_stale(today) {
+            return None;
+        }
+        self.rates.get(currency.code()).and_then(|rate| value.convert(*rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATES: &str = "17.10.2023 #201
+země|měna|množství|kód|kurz
+Austrálie|dolar|1|AUD|14,803
+EMU|euro|1|EUR|24,500
+Japonsko|jen|100|JPY|15,500
+Polsko|zlotý|1|PLN|5,468
+USA|dolar|1|USD|23,341
+Velká Británie|libra|1|GBP|abc
+";
+
+    #[test]
+    fn test_read_rates() {
+        let rates = ExchangeRates::read_rates(RATES, 4);
+        let today = NaiveDate::from_ymd_opt(2023, 10, 17).unwrap();
+        assert_eq!(Some(today), rates.date());
+        assert_eq!(Some(Money::from_units(245)), rates.to_czk_at(Money::from_units(10), Currency::Eur, today));
+        assert_eq!(Some(Money::from_halere(2334)), rates.to_czk_at(Money::from_units(1), Currency::Usd, today));
+        assert_eq!(None, rates.to_czk_at(Money::from_units(1), Currency::Gbp, today));
+        assert_eq!(Some(&0.155), rates.rates.get("JPY"));
+    }
+
+    #[test]
+    fn test_stale_rates() {
+        let rates = ExchangeRates::read_rates(RATES, 4);
+        let today = NaiveDate::from_ymd_opt(2023, 10, 21).unwrap();
+        assert!(!rates.is_stale(today));
+        assert!(rates.to_czk_at(Money::from_units(10), Currency::Eur, today).is_some());
+        let today = NaiveDate::from_ymd_opt(2023, 10, 22).unwrap();
+        assert!(rates.is_stale(today));
+        assert_eq!(None, rates.to_czk_at(Money::from_units(10), Currency::Eur, today));
+    }
+
+    #[test]
+    fn test_invalid_rates() {
+        let rates = ExchangeRates::read_rates("EUR 24,50\nUSD 22,50", 4);
+        assert_eq!(None, rates.date());
+        assert!(rates.is_stale(NaiveDate::from_ymd_opt(2023, 10, 17).unwrap()));
+        let rates = ExchangeRates::read_rates("17.10.2023 #201\nEUR 24,50", 4);
+        assert_eq!(None, rates.date());
+    }
+}
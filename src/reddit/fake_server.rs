@@ -0,0 +1,191 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde_json::json;
+use tokio::{io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader}, net::{TcpListener, TcpStream}};
+
+use super::Comment;
+
+// Local server speaking the part of the Reddit API the bot uses, for end-to-end tests of the HTTP backend.
+// Every request is answered and closed, there is a single subreddit and a single user.
+pub struct FakeReddit {
+    pub url: String,
+    state: Arc<Mutex<FakeState>>
+}
+
+pub struct FakeState {
+    // app credentials accepted by the token endpoint
+    pub client_id: String,
+    pub client_secret: String,
+    // the newest first
+    pub comments: Vec<Comment>,
+    pub user_comments: Vec<Comment>,
+    // parent full name and text, the oldest first
+    pub replies: Vec<(String, String)>,
+    // "GET /r/czech/comments/.json?limit=25"
+    pub requests: Vec<String>,
+    // remaining requests and seconds to reset sent in the headers of every response
+    pub rate_limit: Option<(f64, u64)>,
+    pub login_client: Option<(String, String)>,
+    pub login_form: String,
+    pub user_agent: String
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: String
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+}
+
+impl FakeReddit {
+    pub const TOKEN: &'static str = "fake-token";
+
+    pub async fn start() -> FakeReddit {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(FakeState {
+            client_id: "CLIENT_ID".to_string(),
+            client_secret: "CLIENT_SECRET".to_string(),
+            comments: vec![],
+            user_comments: vec![],
+            replies: vec![],
+            requests: vec![],
+            rate_limit: None,
+            login_client: None,
+            login_form: String::new(),
+            user_agent: String::new()
+        }));
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(Self::handle(stream, server_state.clone()));
+            }
+        });
+        FakeReddit { url, state }
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap()
+    }
+
+    // new comment on the post, it becomes the latest one
+    pub fn add_comment(&self, id: &str, author: &str, post_id: &str, body: &str) {
+        let comment = Comment {
+            id: id.to_string(),
+            name: format!("t1_{}", id),
+            author: author.to_string(),
+            body: body.to_string(),
+            link_id: format!("t3_{}", post_id),
+            link_url: format!("https://www.reddit.com/comments/{}", post_id),
+            created_utc: 0
+        };
+        self.state().comments.insert(0, comment);
+    }
+
+    pub fn replies(&self) -> Vec<(String, String)> {
+        self.state().replies.clone()
+    }
+
+    async fn handle(mut stream: TcpStream, state: Arc<Mutex<FakeState>>) {
+        let Some(request) = Self::read_request(&mut stream).await else {
+            return;
+        };
+        let (status, body, rate_limit) = {
+            let mut state = state.lock().unwrap();
+            state.requests.push(format!("{} {}", request.method, request.path));
+            state.user_agent = request.header("user-agent").unwrap_or("").to_string();
+            let (status, body) = Self::route(&request, &mut state);
+            (status, body, state.rate_limit)
+        };
+        let mut response = format!("HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            status, body.len());
+        if let Some((remaining, reset)) = rate_limit {
+            response += &format!("x-ratelimit-remaining: {:.1}\r\nx-ratelimit-reset: {}\r\n", remaining, reset);
+        }
+        response += &format!("\r\n{}", body);
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok()?;
+        let mut parts = line.split_whitespace();
+        let (method, path) = (parts.next()?.to_string(), parts.next()?.to_string());
+        let mut headers = vec![];
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).await.ok()?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(':')?;
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+        let mut request = Request { method, path, headers, body: String::new() };
+        let length = request.header("content-length").and_then(|l| l.parse::<usize>().ok()).unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await.ok()?;
+        request.body = String::from_utf8(body).ok()?;
+        Some(request)
+    }
+
+    fn route(request: &Request, state: &mut FakeState) -> (&'static str, String) {
+        let path = request.path.split('?').next().unwrap_or("");
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), &segments[..]) {
+            ("POST", ["api", "v1", "access_token"]) => {
+                let client = request.header("authorization")
+                    .and_then(|a| a.strip_prefix("Basic "))
+                    .and_then(|encoded| STANDARD.decode(encoded).ok())
+                    .and_then(|decoded| String::from_utf8(decoded).ok())
+                    .and_then(|c| c.split_once(':').map(|(id, secret)| (id.to_string(), secret.to_string())));
+                state.login_client = client.clone();
+                state.login_form = request.body.clone();
+                match client == Some((state.client_id.clone(), state.client_secret.clone())) {
+                    true => ("200 OK", json!({"access_token": Self::TOKEN, "token_type": "bearer", "expires_in": 3600, "scope": "*"}).to_string()),
+                    false => ("401 Unauthorized", json!({"message": "Unauthorized", "error": 401}).to_string())
+                }
+            },
+            ("GET", ["r", _, "comments", ".json"]) => {
+                let limit = request.path.split("limit=").nth(1).and_then(|l| l.split('&').next()?.parse::<usize>().ok()).unwrap_or(25);
+                ("200 OK", Self::listing(state.comments.iter().take(limit)))
+            },
+            ("GET", ["user", _, "comments", ".json"]) => ("200 OK", Self::listing(state.user_comments.iter())),
+            ("POST", ["api", "comment"]) => {
+                if request.header("authorization") != Some(&format!("Bearer {}", Self::TOKEN)) {
+                    return ("403 Forbidden", json!({"message": "Forbidden", "error": 403}).to_string());
+                }
+                let field = |name: &str| form_urlencoded::parse(request.body.as_bytes())
+                    .find(|(n, _)| n == name)
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default();
+                let (parent_name, text) = (field("thing_id"), field("text"));
+                let Some(parent) = state.comments.iter().find(|c| c.name == parent_name).cloned() else {
+                    return ("200 OK", json!({"json": {"errors": [["THREAD_LOCKED", "unknown parent", "parent"]]}}).to_string());
+                };
+                state.user_comments.insert(0, Comment { author: "USER_NAME".to_string(), body: text.clone(), ..parent });
+                state.replies.push((parent_name, text));
+                ("200 OK", json!({"json": {"errors": [], "data": {"things": []}}}).to_string())
+            },
+            _ => ("404 Not Found", json!({"message": "Not Found", "error": 404}).to_string())
+        }
+    }
+
+    fn listing<'a>(comments: impl Iterator<Item = &'a Comment>) -> String {
+        let children: Vec<serde_json::Value> = comments.map(|c| json!({
+            "kind": "t1",
+            "data": {"id": c.id, "name": c.name, "author": c.author, "body": c.body, "link_id": c.link_id, "link_url": c.link_url}
+        })).collect();
+        json!({"kind": "Listing", "data": {"children": children}}).to_string()
+    }
+}
@@ -0,0 +1,250 @@
+use std::{sync::Mutex, time::Duration};
+
+use reqwest::{Client, RequestBuilder, Response, header::{HeaderMap, USER_AGENT}};
+use roux::comment::Comments;
+use serde::Deserialize;
+
+use crate::config::Config;
+
+use super::{Comment, RedditApi, RedditError};
+
+// Reddit accessed over its HTTP API, reading is anonymous, replying needs OAuth token got by login.
+// Base urls come from the config, so the bot can be pointed to a local server.
+pub struct HttpReddit {
+    client: Client,
+    url: String,
+    oauth_url: String,
+    user_agent: String,
+    token: Option<String>,
+    // from the last response
+    rate_limit: Mutex<Option<RateLimit>>
+}
+
+// "x-ratelimit-remaining" and "x-ratelimit-reset" headers of reddit responses
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub remaining: f64,
+    pub reset_seconds: u64
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String
+}
+
+// reply is answered with 200 even when it fails, errors are listed in the body
+#[derive(Deserialize)]
+struct PostResponse {
+    json: PostErrors
+}
+
+#[derive(Deserialize)]
+struct PostErrors {
+    errors: Vec<serde_json::Value>
+}
+
+impl HttpReddit {
+    // reddit rate limit window is 10 minutes
+    const MAX_RATE_LIMIT_WAIT_SECONDS: u64 = 600;
+
+    pub fn new(config: &Config) -> HttpReddit {
+        HttpReddit {
+            client: Client::new(),
+            url: config.reddit_url.trim_end_matches('/').to_string(),
+            oauth_url: config.reddit_oauth_url.trim_end_matches('/').to_string(),
+            user_agent: config.user_agent.clone(),
+            token: None,
+            rate_limit: Mutex::new(None)
+        }
+    }
+
+    // logs in only when the responses are posted
+    pub async fn connect(config: &Config) -> HttpReddit {
+        let mut reddit = Self::new(config);
+        if !config.post_response {
+            println!("POST_RESPONSE set to false, skipping Login");
+            return reddit;
+        }
+        println!("Loggin in");
+        if let Err(e) = reddit.login(config).await {
+            panic!("Couldn't login to reddit and POST_RESPONSE is set to true: {}", e);
+        }
+        reddit
+    }
+
+    // password grant of a reddit "script" app
+    pub async fn login(&mut self, config: &Config) -> Result<(), RedditError> {
+        let request = self.client.post(format!("{}/api/v1/access_token", self.url))
+            .basic_auth(&config.client_id, Some(&config.client_secret))
+            .form(&[("grant_type", "password"), ("username", &config.user_name), ("password", &config.password)]);
+        let token = self.send(request).await?
+            .json::<AccessToken>().await
+            .map_err(|e| RedditError(format!("Invalid access token response: {}", e)))?;
+        self.token = Some(token.access_token);
+        Ok(())
+    }
+
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().unwrap()
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response, RedditError> {
+        self.wait_for_rate_limit().await;
+        let response = request.header(USER_AGENT, &self.user_agent).send().await
+            .map_err(|e| RedditError(format!("Request failed: {}", e)))?;
+        if let Some(rate_limit) = Self::read_rate_limit(response.headers()) {
+            *self.rate_limit.lock().unwrap() = Some(rate_limit);
+        }
+        if !response.status().is_success() {
+            return Err(RedditError(format!("{} returned {}", response.url().path(), response.status())));
+        }
+        Ok(response)
+    }
+
+    // used up limit is waited out instead of getting banned
+    async fn wait_for_rate_limit(&self) {
+        let Some(rate_limit) = self.rate_limit() else {
+            return;
+        };
+        if rate_limit.remaining < 1.0 {
+            let seconds = rate_limit.reset_seconds.min(Self::MAX_RATE_LIMIT_WAIT_SECONDS);
+            println!("Rate limit used up, waiting {} seconds", seconds);
+            tokio::time::sleep(Duration::from_secs(seconds)).await;
+            *self.rate_limit.lock().unwrap() = None;
+        }
+    }
+
+    fn read_rate_limit(headers: &HeaderMap) -> Option<RateLimit> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(|v| v.trim().to_string());
+        let remaining = header("x-ratelimit-remaining")?.parse::<f64>().ok()?;
+        let reset_seconds = header("x-ratelimit-reset")?.parse::<u64>().ok()?;
+        Some(RateLimit { remaining, reset_seconds })
+    }
+
+    async fn read_comments(&self, url: String) -> Result<Vec<Comment>, RedditError> {
+        let comments = self.send(self.client.get(url)).await?
+            .json::<Comments>().await
+            .map_err(|e| RedditError(format!("Invalid comments response: {}", e)))?;
+        Ok(comments.data.children.into_iter().map(|c| c.data.into()).collect())
+    }
+}
+
+impl RedditApi for HttpReddit {
+    async fn latest_comments(&self, subreddit: &str, limit: u32) -> Result<Vec<Comment>, RedditError> {
+        self.read_comments(format!("{}/r/{}/comments/.json?limit={}", self.url, subreddit, limit)).await
+            .map_err(|e| RedditError(format!("Failed to read comments of r/{}: {}", subreddit, e)))
+    }
+
+    async fn user_comments(&self, user_name: &str) -> Result<Vec<Comment>, RedditError> {
+        self.read_comments(format!("{}/user/{}/comments/.json?t=day", self.url, user_name)).await
+            .map_err(|e| RedditError(format!("Failed to read comments of u/{}: {}", user_name, e)))
+    }
+
+    async fn post_reply(&self, parent_name: &str, text: &str) -> Result<(), RedditError> {
+        let Some(token) = &self.token else {
+            return Err(RedditError("Not logged in".to_string()));
+        };
+        let request = self.client.post(format!("{}/api/comment", self.oauth_url))
+            .bearer_auth(token)
+            .form(&[("api_type", "json"), ("thing_id", parent_name), ("text", text)]);
+        let response = self.send(request).await?
+            .json::<PostResponse>().await
+            .map_err(|e| RedditError(format!("Invalid reply response: {}", e)))?;
+        match response.json.errors.is_empty() {
+            true => Ok(()),
+            false => Err(RedditError(format!("Reply rejected: {:?}", response.json.errors)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::reddit::fake_server::FakeReddit;
+
+    use super::*;
+
+    fn config(fake: &FakeReddit) -> Config {
+        let mut config = Config::create_default_config();
+        config.reddit_url = fake.url.clone();
+        config.reddit_oauth_url = format!("{}/", fake.url);
+        config.post_response = true;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_login() {
+        let fake = FakeReddit::start().await;
+        let config = config(&fake);
+        let reddit = HttpReddit::connect(&config).await;
+        assert_eq!(Some(FakeReddit::TOKEN.to_string()), reddit.token);
+        {
+            let state = fake.state();
+            assert_eq!(vec!["POST /api/v1/access_token".to_string()], state.requests);
+            assert_eq!(Some(("CLIENT_ID".to_string(), "CLIENT_SECRET".to_string())), state.login_client);
+            assert_eq!("grant_type=password&username=USER_NAME&password=PASSWORD", state.login_form);
+            assert_eq!("USER_AGENT", state.user_agent);
+        }
+        // wrong app credentials are refused
+        fake.state().client_secret = "jine".to_string();
+        let mut reddit = HttpReddit::new(&config);
+        assert!(reddit.login(&config).await.is_err());
+        assert_eq!(None, reddit.token);
+    }
+
+    #[tokio::test]
+    async fn test_read_comments() {
+        let fake = FakeReddit::start().await;
+        fake.add_comment("c1", "pepa", "p1", "Stalo to 500 kc");
+        fake.add_comment("c2", "jarda", "p2", "A tohle \"100 kc\"");
+        let reddit = HttpReddit::new(&config(&fake));
+        let comments = reddit.latest_comments("czech", 25).await.unwrap();
+        assert_eq!(2, comments.len());
+        assert_eq!(Comment {
+            id: "c2".to_string(),
+            name: "t1_c2".to_string(),
+            author: "jarda".to_string(),
+            body: "A tohle \"100 kc\"".to_string(),
+            link_id: "t3_p2".to_string(),
+            link_url: "https://www.reddit.com/comments/p2".to_string(),
+            created_utc: 0
+        }, comments[0]);
+        assert!(reddit.user_comments("USER_NAME").await.unwrap().is_empty());
+        assert_eq!(vec!["GET /r/czech/comments/.json?limit=25".to_string(), "GET /user/USER_NAME/comments/.json?t=day".to_string()],
+            fake.state().requests);
+    }
+
+    #[tokio::test]
+    async fn test_post_reply() {
+        let fake = FakeReddit::start().await;
+        fake.add_comment("c1", "pepa", "p1", "Stalo to 500 kc");
+        let config = config(&fake);
+        // posting needs login
+        let reddit = HttpReddit::new(&config);
+        assert!(reddit.post_reply("t1_c1", "reply").await.is_err());
+        let reddit = HttpReddit::connect(&config).await;
+        reddit.post_reply("t1_c1", "> Stalo to 500 kc\n\nTo by stacilo na 12 dvoulitrovek & víc").await.unwrap();
+        assert_eq!(vec![("t1_c1".to_string(), "> Stalo to 500 kc\n\nTo by stacilo na 12 dvoulitrovek & víc".to_string())], fake.replies());
+        // the reply shows up in comments of the bot
+        let comments = reddit.user_comments("USER_NAME").await.unwrap();
+        assert_eq!("t3_p1", comments[0].link_id);
+        assert!(reddit.post_reply("t1_neni", "reply").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let fake = FakeReddit::start().await;
+        fake.state().rate_limit = Some((5.0, 1));
+        let reddit = HttpReddit::new(&config(&fake));
+        reddit.latest_comments("czech", 25).await.unwrap();
+        assert_eq!(Some(RateLimit { remaining: 5.0, reset_seconds: 1 }), reddit.rate_limit());
+        // used up limit is waited out before the next request
+        fake.state().rate_limit = Some((0.0, 1));
+        reddit.latest_comments("czech", 25).await.unwrap();
+        let start = Instant::now();
+        reddit.latest_comments("czech", 25).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        assert_eq!(3, fake.state().requests.len());
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Mutex;
+
+use super::{Comment, RedditApi, RedditError};
+
+// In-memory Reddit for tests - comments of a single subreddit, replies are recorded as comments of the bot
+pub struct MemoryReddit {
+    user_name: String,
+    // the newest first
+    comments: Mutex<Vec<Comment>>,
+    // the newest first
+    user_comments: Mutex<Vec<Comment>>,
+    // parent full name and text, the oldest first
+    replies: Mutex<Vec<(String, String)>>
+}
+
+impl MemoryReddit {
+    pub fn new(user_name: &str) -> MemoryReddit {
+        MemoryReddit { user_name: user_name.to_string(), comments: Mutex::new(vec![]), user_comments: Mutex::new(vec![]), replies: Mutex::new(vec![]) }
+    }
+
+    // new comment on the post, it becomes the latest one
+    pub fn add_comment(&self, id: &str, author: &str, post_id: &str, body: &str) {
+        let comment = Comment {
+            id: id.to_string(),
+            name: format!("t1_{}", id),
+            author: author.to_string(),
+            body: body.to_string(),
+            link_id: format!("t3_{}", post_id),
+            link_url: format!("https://www.reddit.com/comments/{}", post_id),
+            created_utc: 0
+        };
+        self.comments.lock().unwrap().insert(0, comment);
+    }
+
+    pub fn replies(&self) -> Vec<(String, String)> {
+        self.replies.lock().unwrap().clone()
+    }
+}
+
+impl RedditApi for MemoryReddit {
+    async fn latest_comments(&self, _subreddit: &str, limit: u32) -> Result<Vec<Comment>, RedditError> {
+        Ok(self.comments.lock().unwrap().iter().take(limit as usize).cloned().collect())
+    }
+
+    async fn user_comments(&self, user_name: &str) -> Result<Vec<Comment>, RedditError> {
+        match user_name == self.user_name {
+            true => Ok(self.user_comments.lock().unwrap().clone()),
+            false => Err(RedditError(format!("Unknown user {}", user_name)))
+        }
+    }
+
+    async fn post_reply(&self, parent_name: &str, text: &str) -> Result<(), RedditError> {
+        let comments = self.comments.lock().unwrap();
+        let Some(parent) = comments.iter().find(|c| c.name == parent_name) else {
+            return Err(RedditError(format!("Unknown comment {}", parent_name)));
+        };
+        let reply = Comment {
+            author: self.user_name.clone(),
+            body: text.to_string(),
+            link_id: parent.link_id.clone(),
+            link_url: parent.link_url.clone(),
+            ..Default::default()
+        };
+        self.user_comments.lock().unwrap().insert(0, reply);
+        self.replies.lock().unwrap().push((parent_name.to_string(), text.to_string()));
+        Ok(())
+    }
+}
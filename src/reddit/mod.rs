@@ -0,0 +1,60 @@
+use std::fmt;
+
+use roux::comment::CommentData;
+
+#[cfg(test)]
+pub mod fake_server;
+pub mod http;
+#[cfg(test)]
+pub mod memory;
+pub mod roux_client;
+
+// The part of Reddit the bot talks to - reading the subreddit and its own comments, replying.
+// Async functions are fine here, the api is only used through generics within the crate.
+#[allow(async_fn_in_trait)]
+pub trait RedditApi {
+    // the newest comment first
+    async fn latest_comments(&self, subreddit: &str, limit: u32) -> Result<Vec<Comment>, RedditError>;
+    // comments of the user from today, the newest first
+    async fn user_comments(&self, user_name: &str) -> Result<Vec<Comment>, RedditError>;
+    // parent is the full name of the comment - "t1_abc123"
+    async fn post_reply(&self, parent_name: &str, text: &str) -> Result<(), RedditError>;
+}
+
+// comment fields the bot needs, independent of the backend
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Comment {
+    pub id: String,
+    // full name with the kind prefix - "t1_abc123"
+    pub name: String,
+    pub author: String,
+    pub body: String,
+    // full name of the post the comment belongs to - "t3_xyz789"
+    pub link_id: String,
+    pub link_url: String,
+    // unix timestamp, 0 when unknown
+    pub created_utc: i64
+}
+
+impl From<CommentData> for Comment {
+    fn from(data: CommentData) -> Self {
+        Comment {
+            id: data.id.unwrap_or_default(),
+            name: data.name.unwrap_or_default(),
+            author: data.author.unwrap_or_default(),
+            body: data.body.unwrap_or_default(),
+            link_id: data.link_id.unwrap_or_default(),
+            link_url: data.link_url.unwrap_or_default(),
+            created_utc: data.created_utc.unwrap_or_default() as i64
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedditError(pub String);
+
+impl fmt::Display for RedditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
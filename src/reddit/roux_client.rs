@@ -0,0 +1,52 @@
+use roux::{Me, Reddit, Subreddit, User, util::{FeedOption, TimePeriod}};
+
+use crate::config::Config;
+
+use super::{Comment, RedditApi, RedditError};
+
+// Reddit accessed by roux, reading is anonymous, replying needs login
+pub struct RouxReddit {
+    me: Option<Me>
+}
+
+impl RouxReddit {
+    pub async fn login(config: &Config) -> RouxReddit {
+        if !config.post_response {
+            println!("POST_RESPONSE set to false, skipping Login");
+            return RouxReddit { me: None };
+        }
+        println!("Loggin in");
+        let client = Reddit::new(&config.user_agent, &config.client_id, &config.client_secret)
+            .username(&config.user_name)
+            .password(&config.password)
+            .login().await;
+        match client {
+            Err(_) => panic!("Couldn't login to reddit and POST_RESPONSE is set to true"),
+            Ok(me) => RouxReddit { me: Some(me) }
+        }
+    }
+}
+
+impl RedditApi for RouxReddit {
+    async fn latest_comments(&self, subreddit: &str, limit: u32) -> Result<Vec<Comment>, RedditError> {
+        let comments = Subreddit::new(subreddit).latest_comments(None, Some(limit)).await
+            .map_err(|e| RedditError(format!("Failed to read comments of r/{}: {}", subreddit, e)))?;
+        Ok(comments.data.children.into_iter().map(|c| c.data.into()).collect())
+    }
+
+    async fn user_comments(&self, user_name: &str) -> Result<Vec<Comment>, RedditError> {
+        let options = FeedOption { after: None, before: None, limit: None, count: None, period: Some(TimePeriod::Today) };
+        let comments = User::new(user_name).comments(Some(options)).await
+            .map_err(|e| RedditError(format!("Failed to read comments of u/{}: {}", user_name, e)))?;
+        Ok(comments.data.children.into_iter().map(|c| c.data.into()).collect())
+    }
+
+    async fn post_reply(&self, parent_name: &str, text: &str) -> Result<(), RedditError> {
+        let Some(me) = &self.me else {
+            return Err(RedditError("Not logged in".to_string()));
+        };
+        me.comment(text, parent_name).await
+            .map(|_| ())
+            .map_err(|e| RedditError(e.to_string()))
+    }
+}
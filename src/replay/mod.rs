@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use roux::comment::CommentData;
+use serde_json::json;
+
+use crate::{bot::SkipReason, reddit::Comment};
+
+// Comments exported from reddit with a CommentData JSON object per line, i.e.
+//
+// {"id": "k1a2b3", "name": "t1_k1a2b3", "author": "pepa", "body": "Stalo to 500 kc", "link_id": "t3_17abcd", "created_utc": 1697536800.0}
+//
+// comments are ordered the way they were written, the same comment read twice is used once
+pub fn read_dump(dump: &str) -> Vec<Comment> {
+    let mut comments: Vec<Comment> = vec![];
+    let mut ids = HashSet::new();
+    for (index, line) in dump.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let comment: Comment = match serde_json::from_str::<CommentData>(line) {
+            Ok(data) => data.into(),
+            Err(e) => {
+                println!("Invalid comment on line {}: {}", index + 1, e);
+                continue;
+            }
+        };
+        if ids.insert(comment.id.clone()) {
+            comments.push(comment);
+        }
+    }
+    comments.sort_by_key(|c| c.created_utc);
+    comments
+}
+
+// JSON line with the would-be reply or the reason it was skipped
+pub fn outcome_line(comment: &Comment, outcome: &Result<String, SkipReason>) -> String {
+    let mut line = json!({"id": comment.id, "link_id": comment.link_id, "author": comment.author, "body": comment.body});
+    match outcome {
+        Ok(reply) => line["reply"] = json!(reply),
+        Err(reason) => line["skipped"] = json!(reason.to_string())
+    }
+    line.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_dump() {
+        let dump = r#"{"id": "c2", "name": "t1_c2", "author": "jarda", "body": "Mne to stalo 200 kc", "link_id": "t3_p1", "created_utc": 1697536900.0}
+
+{"id": "c1", "name": "t1_c1", "author": "pepa", "body": "Stalo to 100 kc", "link_id": "t3_p1", "created_utc": 1697536800.0, "score": 5}
+neni json
+{"id": "c2", "name": "t1_c2", "author": "jarda", "body": "Mne to stalo 200 kc", "link_id": "t3_p1", "created_utc": 1697536900.0}"#;
+        let comments = read_dump(dump);
+        assert_eq!(2, comments.len());
+        assert_eq!("c1", comments[0].id);
+        assert_eq!("Stalo to 100 kc", comments[0].body);
+        assert_eq!(1697536800, comments[0].created_utc);
+        assert_eq!("t3_p1", comments[1].link_id);
+    }
+
+    #[test]
+    fn test_outcome_line() {
+        let comment = Comment { id: "c1".to_string(), author: "pepa".to_string(), body: "\"100 kc\"".to_string(), link_id: "t3_p1".to_string(), ..Default::default() };
+        assert_eq!(r#"{"author":"pepa","body":"\"100 kc\"","id":"c1","link_id":"t3_p1","reply":"> 100 kc"}"#,
+            outcome_line(&comment, &Ok("> 100 kc".to_string())));
+        assert_eq!(r#"{"author":"pepa","body":"\"100 kc\"","id":"c1","link_id":"t3_p1","skipped":"already 3 comments on the post"}"#,
+            outcome_line(&comment, &Err(SkipReason::PostLimit(3))));
+    }
+}